@@ -6,25 +6,13 @@ fn benchmark(c: &mut Criterion) {
 
     group.bench_function("part 1", |b| {
         let lines = utils::load_input("inputs/day_01").expect("could not load input");
-        let depths = lines
-            .iter()
-            .map(|x| {
-                x.parse::<u64>()
-                    .expect("could not parse invalid integer string")
-            })
-            .collect::<Vec<u64>>();
+        let depths = day_01::parse_input(lines).expect("could not parse input");
 
         b.iter(|| black_box(day_01::part_one(&depths)))
     });
     group.bench_function("part 2", |b| {
         let lines = utils::load_input("inputs/day_01").expect("could not load input");
-        let depths = lines
-            .iter()
-            .map(|x| {
-                x.parse::<u64>()
-                    .expect("could not parse invalid integer string")
-            })
-            .collect::<Vec<u64>>();
+        let depths = day_01::parse_input(lines).expect("could not parse input");
 
         b.iter(|| black_box(day_01::part_two(&depths)))
     });
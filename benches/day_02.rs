@@ -6,35 +6,13 @@ fn benchmark(c: &mut Criterion) {
 
     group.bench_function("part 1", |b| {
         let lines = utils::load_input("inputs/day_02").expect("could not load input");
-        let commands: Vec<(&str, u64)> = lines
-            .iter()
-            .map(|line| {
-                let tokens: Vec<&str> = line.split(" ").collect();
-                (
-                    tokens[0],
-                    tokens[1]
-                        .parse::<u64>()
-                        .expect("could not parse invalid integer string"),
-                )
-            })
-            .collect();
+        let commands = day_02::parse_input(lines).expect("could not parse input");
 
         b.iter(|| black_box(day_02::part_one(&commands)))
     });
     group.bench_function("part 2", |b| {
         let lines = utils::load_input("inputs/day_02").expect("could not load input");
-        let commands: Vec<(&str, u64)> = lines
-            .iter()
-            .map(|line| {
-                let tokens: Vec<&str> = line.split(" ").collect();
-                (
-                    tokens[0],
-                    tokens[1]
-                        .parse::<u64>()
-                        .expect("could not parse invalid integer string"),
-                )
-            })
-            .collect();
+        let commands = day_02::parse_input(lines).expect("could not parse input");
 
         b.iter(|| black_box(day_02::part_two(&commands)))
     });
@@ -6,23 +6,13 @@ fn benchmark(c: &mut Criterion) {
 
     group.bench_function("part 1", |b| {
         let lines = utils::load_input("inputs/day_03").expect("could not load input");
-        let diagnostics: Vec<u64> = lines
-            .iter()
-            .map(|line| {
-                u64::from_str_radix(line, 2).expect("could not parse invalid binary integer string")
-            })
-            .collect();
+        let diagnostics = day_03::parse_input(lines).expect("could not parse input");
 
         b.iter(|| black_box(day_03::part_one(&diagnostics)))
     });
     group.bench_function("part 2", |b| {
         let lines = utils::load_input("inputs/day_03").expect("could not load input");
-        let diagnostics: Vec<u64> = lines
-            .iter()
-            .map(|line| {
-                u64::from_str_radix(line, 2).expect("could not parse invalid binary integer string")
-            })
-            .collect();
+        let diagnostics = day_03::parse_input(lines).expect("could not parse input");
 
         b.iter(|| black_box(day_03::part_two(&diagnostics)))
     });
@@ -1,9 +1,4 @@
-use std::convert::TryFrom;
-
-use aoc_2021::{
-    day_04::{self, BingoBoard},
-    utils,
-};
+use aoc_2021::{day_04, utils};
 use criterion::{black_box, criterion_group, Criterion};
 
 fn benchmark(c: &mut Criterion) {
@@ -11,16 +6,7 @@ fn benchmark(c: &mut Criterion) {
 
     group.bench_function("part 1", |b| {
         let lines = utils::load_input("inputs/day_04").expect("could not load input");
-        let mut sections = lines.split(|line| line.is_empty());
-
-        let sequence: Vec<i64> = sections.next().expect("invalid input")[0]
-            .split(",")
-            .map(|x| x.parse::<i64>().expect("could not parse integer string"))
-            .collect();
-
-        let boards: Vec<BingoBoard> = sections
-            .map(|section| BingoBoard::try_from(section).expect("could not parse bingo board"))
-            .collect();
+        let (sequence, boards) = day_04::parse_input(lines).expect("could not parse input");
 
         b.iter(|| {
             let mut boards = boards.clone();
@@ -29,16 +15,7 @@ fn benchmark(c: &mut Criterion) {
     });
     group.bench_function("part 2", |b| {
         let lines = utils::load_input("inputs/day_04").expect("could not load input");
-        let mut sections = lines.split(|line| line.is_empty());
-
-        let sequence: Vec<i64> = sections.next().expect("invalid input")[0]
-            .split(",")
-            .map(|x| x.parse::<i64>().expect("could not parse integer string"))
-            .collect();
-
-        let boards: Vec<BingoBoard> = sections
-            .map(|section| BingoBoard::try_from(section).expect("could not parse bingo board"))
-            .collect();
+        let (sequence, boards) = day_04::parse_input(lines).expect("could not parse input");
 
         b.iter(|| {
             let mut boards = boards.clone();
@@ -1,7 +1,60 @@
 use aoc_2021::{
-    utils, day_12,
+    utils, day_12::{self, CaveSystem, Counting, VisitPolicy},
 };
-use criterion::{black_box, criterion_group, Criterion};
+use criterion::{black_box, criterion_group, BenchmarkId, Criterion};
+
+/// Generates a cave system with the given number of small caves, each joined to a few others and
+/// to a handful of big caves, so that the ways of counting can be compared on something larger
+/// than the real input. Big caves are never joined to each other, which would allow endless paths
+fn generate(small: usize, big: usize) -> CaveSystem {
+    // xorshift, seeded so that every run benchmarks the same cave system
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = |n: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as usize % n
+    };
+
+    let small_names: Vec<String> = (0..small).map(|i| format!("s{}", i)).collect();
+    let big_names: Vec<String> = (0..big).map(|i| format!("B{}", i)).collect();
+    let mut lines = vec![
+        format!("start-{}", small_names[0]),
+        format!("start-{}", big_names[0]),
+        format!("{}-end", small_names[small - 1]),
+        format!("{}-end", big_names[big - 1]),
+    ];
+    for (i, name) in small_names.iter().enumerate() {
+        lines.push(format!("{}-{}", name, small_names[(i + 1) % small]));
+        lines.push(format!("{}-{}", name, small_names[next(small)]));
+        lines.push(format!("{}-{}", name, big_names[next(big)]));
+    }
+    lines.retain(|line| {
+        let mut caves = line.split('-');
+        caves.next() != caves.next()
+    });
+
+    let edges = day_12::parse_input(lines).expect("could not parse generated input");
+    day_12::cave_system(&edges).expect("could not build generated cave system")
+}
+
+/// Generates a chain of small caves, each joined to the next, with a big cave joined to every run
+/// of 3 small caves. There are far too many paths to walk one at a time, but few enough ways of
+/// reaching each cave that they can be counted by remembering them
+fn chain(small: usize) -> CaveSystem {
+    let mut lines = vec!["start-s0".to_string(), format!("s{}-end", small - 1)];
+    for i in 0..small {
+        if i + 1 < small {
+            lines.push(format!("s{}-s{}", i, i + 1));
+        }
+        for j in i..small.min(i + 3) {
+            lines.push(format!("s{}-B{}", j, i));
+        }
+    }
+
+    let edges = day_12::parse_input(lines).expect("could not parse generated input");
+    day_12::cave_system(&edges).expect("could not build generated cave system")
+}
 
 fn benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("day 12: passage pathing");
@@ -11,7 +64,7 @@ fn benchmark(c: &mut Criterion) {
         let edges = day_12::parse_input(lines).expect("could not parse input");
 
         b.iter(|| {
-            black_box(day_12::part_one(edges.clone()))
+            black_box(day_12::part_one(&edges))
         })
     });
     group.bench_function("part 2", |b| {
@@ -19,7 +72,34 @@ fn benchmark(c: &mut Criterion) {
         let edges = day_12::parse_input(lines).expect("could not parse input");
 
         b.iter(|| {
-            black_box(day_12::part_two(edges.clone()))
+            black_box(day_12::part_two(&edges))
+        })
+    });
+    group.finish();
+
+    let lines = utils::load_input("inputs/day_12").expect("could not load input");
+    let edges = day_12::parse_input(lines).expect("could not parse input");
+    let real = day_12::cave_system(&edges).expect("could not build cave system");
+    let generated = generate(10, 3);
+    let inputs = [("real", &real), ("generated", &generated)];
+
+    let mut group = c.benchmark_group("day 12: counting");
+    group.sample_size(10);
+    for (name, cave_system) in inputs {
+        for counting in Counting::ALL {
+            group.bench_with_input(BenchmarkId::new(counting.name(), name), &counting, |b, counting| {
+                b.iter(|| {
+                    black_box(cave_system.number_of_paths_with(VisitPolicy::PART_TWO, *counting))
+                })
+            });
+        }
+    }
+
+    // only the memoised count finishes on this one
+    let chain = chain(20);
+    group.bench_function(BenchmarkId::new(Counting::Memoised.name(), "chain"), |b| {
+        b.iter(|| {
+            black_box(chain.number_of_paths_with(VisitPolicy::PART_TWO, Counting::Memoised))
         })
     });
     group.finish();
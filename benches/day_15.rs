@@ -1,7 +1,31 @@
 use aoc_2021::{
-    utils, day_15,
+    utils, day_15::{self, Algorithm, Cavern},
 };
-use criterion::{black_box, criterion_group, Criterion};
+use criterion::{black_box, criterion_group, BenchmarkId, Criterion};
+
+/// Generates a square cavern of the given size with pseudo random risk levels, so that the
+/// algorithms can be compared on something larger than the real input. The cavern is a single
+/// tile, so the whole of it is searched
+fn generate(size: usize) -> Cavern {
+    // xorshift, seeded so that every run benchmarks the same cavern
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let lines = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    std::char::from_digit((state % 9 + 1) as u32, 10).unwrap_or('9')
+                })
+                .collect::<String>()
+        })
+        .collect();
+
+    day_15::parse_input(lines)
+        .and_then(|cavern| cavern.with_tiles(1))
+        .expect("could not parse generated input")
+}
 
 fn benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("day 15: chiton");
@@ -23,6 +47,28 @@ fn benchmark(c: &mut Criterion) {
         })
     });
     group.finish();
+
+    let lines = utils::load_input("inputs/day_15").expect("could not load input");
+    let real = day_15::parse_input(lines).expect("could not parse input");
+    let generated = generate(1000);
+    let inputs = [
+        ("real", &real, real.southeast_corner()),
+        ("real tiled", &real, real.tiled_southeast_corner()),
+        ("generated 1000x1000", &generated, generated.southeast_corner()),
+    ];
+
+    let mut group = c.benchmark_group("day 15: algorithms");
+    group.sample_size(10);
+    for (name, cavern, target) in inputs {
+        for algorithm in Algorithm::ALL {
+            group.bench_with_input(BenchmarkId::new(algorithm.name(), name), &target, |b, target| {
+                b.iter(|| {
+                    black_box(cavern.lowest_risk_path_with(*target, algorithm))
+                })
+            });
+        }
+    }
+    group.finish();
 }
 
 criterion_group!(benches, benchmark);
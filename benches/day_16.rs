@@ -1,5 +1,7 @@
+use std::fs::File;
+
 use aoc_2021::{
-    utils, day_16,
+    utils, day_16::{self, Decoder},
 };
 use criterion::{black_box, criterion_group, Criterion};
 
@@ -22,6 +24,12 @@ fn benchmark(c: &mut Criterion) {
             black_box(day_16::part_two(&transmission))
         })
     });
+    group.bench_function("streamed", |b| {
+        b.iter(|| {
+            let file = File::open("inputs/day_16").expect("could not open input");
+            black_box(day_16::stream_summary(Decoder::hex(file)))
+        })
+    });
     group.finish();
 }
 
@@ -1,16 +1,8 @@
-use aoc_2021::utils;
-use aoc_2021::day_01;
+use aoc_2021::{day_01, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_01").expect("could not load input");
-    let depths = lines
-        .iter()
-        .map(|x| {
-            x.parse::<u64>()
-                .expect("could not parse invalid integer string")
-        })
-        .collect::<Vec<u64>>();
-
-    println!("part 1: {}", day_01::part_one(&depths));
-    println!("part 2: {}", day_01::part_two(&depths));
+    if let Err(e) = Day::new::<day_01::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
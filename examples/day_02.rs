@@ -1,16 +1,8 @@
-use aoc_2021::{utils, day_02};
-
+use aoc_2021::{day_02, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_02").expect("could not load input");
-    let commands: Vec<(&str, u64)> = lines.iter().map(|line| {
-        let tokens: Vec<&str> = line.split(" ").collect();
-        (
-            tokens[0],
-            tokens[1].parse::<u64>().expect("could not parse invalid integer string")
-        )
-    }).collect();
-    
-    println!("part 1: {}", day_02::part_one(&commands));
-    println!("part 2: {}", day_02::part_two(&commands));
+    if let Err(e) = Day::new::<day_02::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
@@ -1,9 +1,8 @@
-use aoc_2021::{day_07::{parse_input, self}, utils};
+use aoc_2021::{day_07, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_07").expect("could not load input");
-    let fleet = parse_input(lines).expect("could not parse input");
-
-    println!("part 1: {}", day_07::part_one(&fleet));
-    println!("part 2: {}", day_07::part_two(&fleet));
+    if let Err(e) = Day::new::<day_07::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
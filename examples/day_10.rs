@@ -1,9 +1,8 @@
-use aoc_2021::{day_10::{parse_input, self}, utils};
+use aoc_2021::{day_10, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_10").expect("could not load input");
-    let subsystem = parse_input(lines).expect("could not parse input");
-
-    println!("part 1: {}", day_10::part_one(&subsystem));
-    println!("part 2: {}", day_10::part_two(&subsystem));
+    if let Err(e) = Day::new::<day_10::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
@@ -1,9 +1,8 @@
-use aoc_2021::{day_11::{parse_input, self}, utils};
+use aoc_2021::{day_11, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_11").expect("could not load input");
-    let energy_map = parse_input(lines).expect("could not parse input");
-
-    println!("part 1: {}", day_11::part_one(&energy_map));
-    println!("part 2: {}", day_11::part_two(&energy_map));
+    if let Err(e) = Day::new::<day_11::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
@@ -1,9 +1,8 @@
-use aoc_2021::{day_12::{parse_input, self}, utils};
+use aoc_2021::{day_12, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_12").expect("could not load input");
-    let edges = parse_input(lines).expect("could not parse input");
-
-    println!("part 1: {}", day_12::part_one(edges.clone()));
-    println!("part 2: {}", day_12::part_two(edges.clone()));
+    if let Err(e) = Day::new::<day_12::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,86 @@
+use std::{env, process};
+
+use aoc_2021::{
+    day_12::{self, Cave, CaveSystem, VisitPolicy},
+    error::AocError,
+    solution, utils,
+};
+
+const USAGE: &str = "usage: day_12_dot [--path <cave>,<cave>,... | --shortest | --longest]
+
+Prints the cave system from inputs/day_12 (or AOC_INPUT) as a Graphviz graph, which can be drawn
+with `dot -Tsvg`. Pass --path with a comma separated list of caves to highlight a path, or
+--shortest or --longest to highlight the path with the lowest or highest total weight.";
+
+enum Highlight {
+    Path(String),
+    Shortest,
+    Longest,
+}
+
+fn main() {
+    let mut highlight = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--path" => match args.next() {
+                Some(caves) => highlight = Some(Highlight::Path(caves)),
+                None => {
+                    eprintln!("{}", USAGE);
+                    process::exit(2);
+                }
+            },
+            "--shortest" => highlight = Some(Highlight::Shortest),
+            "--longest" => highlight = Some(Highlight::Longest),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let cave_system = utils::load_input(&solution::input_path(12))
+        .and_then(day_12::parse_input)
+        .and_then(|edges| day_12::cave_system(&edges));
+
+    match cave_system.and_then(|cave_system| {
+        let path = highlighted_path(&cave_system, highlight)?;
+        Ok(cave_system.to_dot(path.as_deref()))
+    }) {
+        Ok(dot) => print!("{}", dot),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn highlighted_path(
+    cave_system: &CaveSystem,
+    highlight: Option<Highlight>,
+) -> Result<Option<Vec<Cave>>, AocError> {
+    let found = match highlight {
+        None => return Ok(None),
+        Some(Highlight::Path(caves)) => {
+            return caves
+                .split(',')
+                .map(|name| {
+                    cave_system
+                        .cave(name)
+                        .ok_or_else(|| AocError::invalid(format!("expected a cave named {}", name)))
+                })
+                .collect::<Result<Vec<Cave>, AocError>>()
+                .map(Some);
+        }
+        Some(Highlight::Shortest) => cave_system.shortest_path(VisitPolicy::PART_ONE),
+        Some(Highlight::Longest) => cave_system.longest_path(VisitPolicy::PART_ONE),
+    };
+
+    match found {
+        Some(path) => {
+            eprintln!("total weight: {}", path.cost);
+            Ok(Some(path.nodes))
+        }
+        None => Err(AocError::no_solution("could not find a path from start to end")),
+    }
+}
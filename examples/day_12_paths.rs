@@ -0,0 +1,52 @@
+use std::{env, process};
+
+use aoc_2021::{
+    day_12::{self, PathFilter, VisitPolicy},
+    solution, utils,
+};
+
+const USAGE: &str = "usage: day_12_paths [--more-time] [--max-len <n>] [--must-visit <cave>]...
+
+Prints every path through the cave system from inputs/day_12 (or AOC_INPUT), one per line, as it
+is found. Pass --more-time to allow one small cave to be visited twice, --max-len to limit the
+number of passages in a path and --must-visit to only print paths through a cave.";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let mut policy = VisitPolicy::PART_ONE;
+    let mut filter = PathFilter::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--more-time" => policy = VisitPolicy::PART_TWO,
+            "--max-len" => match args.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) => filter = filter.max_len(n),
+                None => usage(),
+            },
+            "--must-visit" => match args.next() {
+                Some(name) => filter = filter.must_visit(&name),
+                None => usage(),
+            },
+            _ => usage(),
+        }
+    }
+
+    let cave_system = utils::load_input(&solution::input_path(12))
+        .and_then(day_12::parse_input)
+        .and_then(|edges| day_12::cave_system(&edges));
+    let cave_system = match cave_system {
+        Ok(cave_system) => cave_system,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    for path in cave_system.paths(policy, filter) {
+        println!("{}", path.join(","));
+    }
+}
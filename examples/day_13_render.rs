@@ -0,0 +1,81 @@
+use std::{env, fs::File, io::BufWriter, process};
+
+use aoc_2021::{
+    day_13::{self, Style},
+    error::AocError,
+    image::ImageFormat,
+    solution, utils,
+};
+
+const USAGE: &str = "usage: day_13_render [--folds <n>] [--crop] [--scale <n>] [--braille]
+                     [--pbm <file> | --png <file>]
+
+Renders the transparent paper from inputs/day_13 (or AOC_INPUT, such as inputs/day_13.large)
+after every fold, or only the first n with --folds. Pass --crop to draw only the box around the
+dots, --scale to shrink each n by n square of the sheet to one cell and --braille to draw 2 by 4
+cells per character. With --pbm or --png the sheet is written to an image file instead.";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let mut folds = None;
+    let mut crop = false;
+    let mut scale = 1;
+    let mut style = Style::Blocks;
+    let mut image = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--crop" => crop = true,
+            "--braille" => style = Style::Braille,
+            "--folds" => match args.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) => folds = Some(n),
+                None => usage(),
+            },
+            "--scale" => match args.next().and_then(|n| n.parse::<u64>().ok()) {
+                Some(n) if n > 0 => scale = n,
+                _ => usage(),
+            },
+            "--pbm" | "--png" => {
+                let format = if arg == "--pbm" {
+                    ImageFormat::Pbm
+                } else {
+                    ImageFormat::Png
+                };
+                match args.next() {
+                    Some(path) => image = Some((format, path)),
+                    None => usage(),
+                }
+            }
+            _ => usage(),
+        }
+    }
+
+    let result = utils::load_input(&solution::input_path(13))
+        .and_then(day_13::parse_input)
+        .and_then(|mut manual| {
+            let folds = folds.unwrap_or(manual.folds().len()).min(manual.folds().len());
+            for i in 0..folds {
+                manual.single_fold(i);
+            }
+
+            match &image {
+                Some((format, path)) => {
+                    let file = File::create(path).map_err(AocError::from)?;
+                    manual.write_image(BufWriter::new(file), *format, crop, scale)
+                }
+                None => {
+                    println!("{}", manual.render(style, crop, scale));
+                    Ok(())
+                }
+            }
+        });
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,78 @@
+use std::{env, process, str::FromStr};
+
+use aoc_2021::{
+    day_15::{self, Algorithm, Highlight},
+    solution, utils,
+};
+
+const USAGE: &str = "usage: day_15_path [--tiled] [--tiles <n>] [--ansi] [--algorithm <name>]
+
+Prints the chiton cavern from inputs/day_15 (or AOC_INPUT) with the lowest risk path highlighted.
+Pass --tiled to search and render the full cavern, tiled 5 times in each direction unless
+--tiles says otherwise, and --ansi to keep every risk level and
+colour the path instead of replacing the rest of the cavern with dots. The algorithm is one of
+dijkstra, astar, bidirectional or bucket (the default).";
+
+fn main() {
+    let mut tiled = false;
+    let mut highlight = Highlight::Dots;
+    let mut algorithm = Algorithm::default();
+    let mut tiles = 5;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tiled" => tiled = true,
+            "--ansi" => highlight = Highlight::Ansi,
+            "--tiles" => match args.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => tiles = n,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    process::exit(2);
+                }
+            },
+            "--algorithm" => match args.next().map(|name| Algorithm::from_str(&name)) {
+                Some(Ok(chosen)) => algorithm = chosen,
+                Some(Err(e)) => {
+                    eprintln!("{}\n\n{}", e, USAGE);
+                    process::exit(2);
+                }
+                None => {
+                    eprintln!("{}", USAGE);
+                    process::exit(2);
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let cavern = utils::load_input(&solution::input_path(15))
+        .and_then(day_15::parse_input)
+        .and_then(|cavern| cavern.with_tiles(tiles));
+    let cavern = match cavern {
+        Ok(cavern) => cavern,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    let target = if tiled {
+        cavern.tiled_southeast_corner()
+    } else {
+        cavern.southeast_corner()
+    };
+
+    match cavern.lowest_risk_path_with(target, algorithm) {
+        Some(path) => {
+            println!("{}", cavern.render(tiled, &path.nodes, highlight));
+            println!("total risk: {}", path.cost);
+        }
+        None => {
+            eprintln!("could not find a path");
+            process::exit(1);
+        }
+    }
+}
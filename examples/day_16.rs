@@ -1,9 +1,8 @@
-use aoc_2021::{day_16::{parse_input, self}, utils};
+use aoc_2021::{day_16, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_16").expect("could not load input");
-    let transmission = parse_input(lines).expect("could not parse input");
-
-    println!("part 1: {}", day_16::part_one(&transmission));
-    println!("part 2: {}", day_16::part_two(&transmission));
+    if let Err(e) = Day::new::<day_16::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
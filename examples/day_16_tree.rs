@@ -0,0 +1,67 @@
+use std::{env, process, str::FromStr};
+
+use aoc_2021::{
+    day_16::{self, Mode, Transmission},
+    error::AocError,
+    solution, utils,
+};
+
+const USAGE: &str = "usage: day_16_tree [--outline | --infix | --sexpr] [--lenient] [--bits | --base64] [<transmission>]
+
+Prints the packet tree of the given transmission, or of inputs/day_16 (or AOC_INPUT) when none is
+given. The transmission is read as hex unless --bits or --base64 says it is a string of 0 and 1
+characters or base64. The tree is printed as an indented outline with the version, type and bit range of every
+packet by default, or as an infix or S-expression with --infix or --sexpr. With --lenient, malformed
+packets are reported on stderr and skipped where possible instead of stopping the decode.";
+
+enum Format {
+    Outline,
+    Infix,
+    Sexpr,
+}
+
+fn main() {
+    let mut format = Format::Outline;
+    let mut mode = Mode::Strict;
+    let mut parse: fn(&str) -> Result<Transmission, AocError> = Transmission::from_str;
+    let mut transmission = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--outline" => format = Format::Outline,
+            "--infix" => format = Format::Infix,
+            "--sexpr" => format = Format::Sexpr,
+            "--lenient" => mode = Mode::Lenient,
+            "--bits" => parse = Transmission::from_bits,
+            "--base64" => parse = Transmission::from_base64,
+            _ if !arg.starts_with('-') && transmission.is_none() => transmission = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(2);
+            }
+        }
+    }
+
+    let transmission = match transmission {
+        Some(transmission) => parse(&transmission),
+        None => utils::load_input(&solution::input_path(16)).and_then(day_16::parse_input),
+    };
+    let decoded = transmission.and_then(|transmission| Ok(transmission.decode(mode)?));
+    let packet = match decoded {
+        Ok(decoded) => {
+            for diagnostic in decoded.diagnostics {
+                eprintln!("{}", diagnostic);
+            }
+            decoded.packet
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    match format {
+        Format::Outline => println!("{}", packet.outline()),
+        Format::Infix => println!("{}", packet),
+        Format::Sexpr => println!("{}", packet.sexpr()),
+    }
+}
@@ -0,0 +1,285 @@
+use std::{collections::BTreeMap, fs, str::FromStr};
+
+use crate::{
+    error::{AocError, Result},
+    solution::Part,
+};
+
+/// The default location of the answers manifest
+pub const ANSWERS_PATH: &str = "inputs/answers.toml";
+
+/// The answers recorded for a single day. A part without a recorded answer is not checked
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub part_one: Option<String>,
+    pub part_two: Option<String>,
+}
+
+impl Expected {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part_one.as_deref(),
+            Part::Two => self.part_two.as_deref(),
+        }
+    }
+}
+
+/// The outcome of checking a single answer against the manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Match,
+    Mismatch { expected: String },
+    Unrecorded,
+}
+
+/// The recorded answers for every day, read from a small subset of TOML:
+///
+/// ```toml
+/// [day_01]
+/// part_one = 1655
+/// part_two = "1683"
+/// ```
+///
+/// Values can be integers, basic strings, literal strings or multi-line literal strings (for
+/// answers that are rendered over several lines)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    days: BTreeMap<u8, Expected>,
+}
+
+impl Manifest {
+    pub fn load(path: &str) -> Result<Self> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn get(&self, day: u8) -> Option<&Expected> {
+        self.days.get(&day)
+    }
+
+    /// Checks an answer for the given day and part against the recorded one
+    pub fn check(&self, day: u8, part: Part, actual: &str) -> Verdict {
+        match self.get(day).and_then(|expected| expected.get(part)) {
+            Some(expected) if expected == actual => Verdict::Match,
+            Some(expected) => Verdict::Mismatch {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unrecorded,
+        }
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut days: BTreeMap<u8, Expected> = BTreeMap::new();
+        let mut current: Option<u8> = None;
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+        while let Some((number, line)) = lines.next() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if let Some(section) = trimmed.strip_prefix('[') {
+                let day = section
+                    .strip_suffix(']')
+                    .and_then(|name| name.strip_prefix("day_"))
+                    .and_then(|day| day.parse::<u8>().ok())
+                    .ok_or_else(|| AocError::parse("expected a [day_XX] section", line))
+                    .map_err(|e| e.at_line(number))?;
+                if days.insert(day, Expected::default()).is_some() {
+                    return Err(AocError::parse("duplicate section", line).at_line(number));
+                }
+                current = Some(day);
+                continue;
+            }
+
+            let (key, value) = trimmed.split_once('=').ok_or_else(|| {
+                AocError::parse("expected a key = value pair", line).at_line(number)
+            })?;
+            let day = current.ok_or_else(|| {
+                AocError::parse("expected a [day_XX] section before any answers", line)
+                    .at_line(number)
+            })?;
+
+            let value = value.trim();
+            let value = if let Some(rest) = value.strip_prefix("'''") {
+                parse_multi_line(rest, &mut lines).map_err(|e| e.at_line(number))?
+            } else {
+                parse_value(value).map_err(|e| e.at_line(number))?
+            };
+
+            let expected = days.entry(day).or_default();
+            let slot = match key.trim() {
+                "part_one" => &mut expected.part_one,
+                "part_two" => &mut expected.part_two,
+                _ => return Err(AocError::parse("unknown key", key.trim()).at_line(number)),
+            };
+            if slot.replace(value).is_some() {
+                return Err(AocError::parse("duplicate key", key.trim()).at_line(number));
+            }
+        }
+
+        Ok(Self { days })
+    }
+}
+
+/// Parses a single line value, which is an integer, a basic string or a literal string
+fn parse_value(value: &str) -> Result<String> {
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return end_of_value(chars.as_str(), parsed),
+                '\\' => match chars.next() {
+                    Some('n') => parsed.push('\n'),
+                    Some('t') => parsed.push('\t'),
+                    Some('"') => parsed.push('"'),
+                    Some('\\') => parsed.push('\\'),
+                    _ => return Err(AocError::parse("invalid escape sequence", value)),
+                },
+                _ => parsed.push(c),
+            }
+        }
+
+        return Err(AocError::parse("unterminated string", value));
+    }
+
+    if let Some(rest) = value.strip_prefix('\'') {
+        let (parsed, remainder) = rest
+            .split_once('\'')
+            .ok_or_else(|| AocError::parse("unterminated string", value))?;
+        return end_of_value(remainder, parsed.to_string());
+    }
+
+    let (integer, remainder) = value.split_at(
+        value
+            .find(|c: char| c.is_whitespace() || c == '#')
+            .unwrap_or(value.len()),
+    );
+    let integer = integer.replace('_', "");
+    if integer.is_empty() || integer.parse::<i128>().is_err() {
+        return Err(AocError::parse("expected an integer or a string", value));
+    }
+
+    end_of_value(remainder, integer)
+}
+
+/// Parses a multi-line literal string, whose opening delimiter has already been consumed. As in
+/// TOML, a newline directly after the opening delimiter is dropped
+fn parse_multi_line<'a>(
+    first: &str,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<String> {
+    if let Some((parsed, remainder)) = first.split_once("'''") {
+        return end_of_value(remainder, parsed.to_string());
+    }
+
+    let mut parsed: Vec<&str> = Vec::new();
+    if !first.is_empty() {
+        parsed.push(first);
+    }
+
+    for (_, line) in lines {
+        if let Some((last, remainder)) = line.split_once("'''") {
+            parsed.push(last);
+            return end_of_value(remainder, parsed.join("\n"));
+        }
+        parsed.push(line);
+    }
+
+    Err(AocError::parse("unterminated multi-line string", first))
+}
+
+/// Checks that nothing but whitespace or a comment follows a value
+fn end_of_value(remainder: &str, parsed: String) -> Result<String> {
+    let remainder = remainder.trim();
+    if remainder.is_empty() || remainder.starts_with('#') {
+        Ok(parsed)
+    } else {
+        Err(AocError::parse(
+            "unexpected characters after value",
+            remainder,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let input = "# recorded answers
+                     [day_01]
+                     part_one = 1_655 # a comment
+                     part_two = \"a \\\"quoted\\\" answer\"
+
+                     [day_13]
+                     part_one = '592'
+                     part_two = '''
+                     ..#
+                     ##.'''";
+
+        let manifest = input
+            .lines()
+            .map(str::trim)
+            .collect::<Vec<&str>>()
+            .join("\n")
+            .parse::<Manifest>()
+            .expect("could not parse manifest");
+
+        assert_eq!(
+            manifest.get(1),
+            Some(&Expected {
+                part_one: Some("1655".to_string()),
+                part_two: Some("a \"quoted\" answer".to_string()),
+            })
+        );
+        assert_eq!(
+            manifest.get(13),
+            Some(&Expected {
+                part_one: Some("592".to_string()),
+                part_two: Some("..#\n##.".to_string()),
+            })
+        );
+        assert_eq!(manifest.get(2), None);
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let e = "[day_01]\npart_one = 1\npart_one = 2"
+            .parse::<Manifest>()
+            .unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "parse error at line 3: duplicate key in \"part_one\""
+        );
+
+        assert!("part_one = 1".parse::<Manifest>().is_err());
+        assert!("[day_01]\npart_three = 1".parse::<Manifest>().is_err());
+        assert!("[day_01]\npart_one = \"1".parse::<Manifest>().is_err());
+        assert!("[day_01]\npart_one = '''1".parse::<Manifest>().is_err());
+        assert!("[day_01]\npart_one = one".parse::<Manifest>().is_err());
+    }
+
+    #[test]
+    fn check_test() {
+        let manifest = "[day_01]\npart_one = 1655"
+            .parse::<Manifest>()
+            .expect("could not parse manifest");
+
+        assert_eq!(manifest.check(1, Part::One, "1655"), Verdict::Match);
+        assert_eq!(
+            manifest.check(1, Part::One, "1656"),
+            Verdict::Mismatch {
+                expected: "1655".to_string()
+            }
+        );
+        assert_eq!(manifest.check(1, Part::Two, "1683"), Verdict::Unrecorded);
+        assert_eq!(manifest.check(2, Part::One, "0"), Verdict::Unrecorded);
+    }
+}
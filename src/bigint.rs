@@ -0,0 +1,207 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Mul},
+};
+
+/// An arbitrary precision unsigned integer, for answers that do not fit in a `u64`. Only the
+/// operations the puzzles need are supported
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// 32 bit limbs, least significant first, without trailing zero limbs
+    limbs: Vec<u32>,
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        let mut big = Self {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        };
+        big.normalise();
+        big
+    }
+}
+
+impl BigUint {
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Returns the value as a `u64`, if it fits
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs.as_slice() {
+            [] => Some(0),
+            [low] => Some(*low as u64),
+            [low, high] => Some((*high as u64) << 32 | *low as u64),
+            _ => None,
+        }
+    }
+
+    /// The number of significant bits, which is 0 for zero
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() * 32 - last.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    /// Returns the `i`th group of 4 bits, counting from the least significant
+    pub fn nibble(&self, i: usize) -> u8 {
+        self.limbs
+            .get(i / 8)
+            .map(|limb| (limb >> ((i % 8) * 4)) as u8 & 0b1111)
+            .unwrap_or(0)
+    }
+
+    /// Multiplies by a small factor and then adds a small term, in place
+    pub fn mul_add(&mut self, factor: u32, term: u32) {
+        let mut carry = term as u64;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        self.limbs.push(carry as u32);
+        self.normalise();
+    }
+
+    /// Divides by a small divisor in place, returning the remainder
+    fn div_rem(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0_u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let current = remainder << 32 | *limb as u64;
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        self.normalise();
+        remainder as u32
+    }
+
+    fn normalise(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0_u64;
+        for i in 0..len {
+            let sum = *self.limbs.get(i).unwrap_or(&0) as u64
+                + *other.limbs.get(i).unwrap_or(&0) as u64
+                + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+
+        let mut big = BigUint { limbs };
+        big.normalise();
+        big
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0_u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                let product = *a as u64 * *b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        let mut big = BigUint { limbs };
+        big.normalise();
+        big
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // peel off 9 decimal digits at a time, least significant first
+        let mut rest = self.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem(1_000_000_000));
+        }
+
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_test() {
+        let max = BigUint::from(u64::MAX);
+        let one = BigUint::from(1);
+
+        let sum = &max + &one;
+        assert_eq!(sum.to_u64(), None);
+        assert_eq!(sum.to_string(), "18446744073709551616");
+        assert_eq!(sum.bits(), 65);
+
+        let product = &max * &max;
+        assert_eq!(product.to_string(), "340282366920938463426481119284349108225");
+        assert_eq!(&max * &BigUint::default(), BigUint::default());
+
+        assert!(sum > max);
+        assert!(one < max);
+        assert_eq!(BigUint::from(1234).to_u64(), Some(1234));
+        assert_eq!(BigUint::default().to_string(), "0");
+    }
+
+    #[test]
+    fn nibble_test() {
+        let mut big = BigUint::default();
+        for nibble in [0xA, 0xB, 0xC, 0xD, 0xE, 0xF, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0x0, 0xA] {
+            big.mul_add(16, nibble);
+        }
+
+        assert_eq!(big.bits(), 68);
+        assert_eq!(big.nibble(0), 0xA);
+        assert_eq!(big.nibble(16), 0xA);
+        assert_eq!(big.nibble(15), 0xB);
+        assert_eq!(big.nibble(17), 0);
+    }
+}
@@ -0,0 +1,391 @@
+use std::{env, process, time::Duration};
+
+use aoc_2021::{
+    answers::{self, Manifest, Verdict},
+    solution::{self, Day, Part, Report},
+    utils, DAYS,
+};
+
+const USAGE: &str = "usage:
+  aoc run <day> [--part <1|2>] [--input <path|->]
+  aoc bench <day> [--part <1|2>] [--input <path|->] [--iterations <n>]
+  aoc test-example <day> [--part <1|2>]
+  aoc all
+  aoc verify [<day>] [--part <1|2>] [--answers <path>]
+
+The input defaults to inputs/day_XX, or AOC_INPUT if set. Pass `--input -` to read stdin.
+`verify` checks answers on the real inputs against inputs/answers.toml.";
+
+#[derive(Debug)]
+struct Options {
+    day: Option<&'static Day>,
+    parts: Vec<Part>,
+    input: Option<String>,
+    answers: Option<String>,
+    iterations: usize,
+    /// The options that were passed, so that commands can reject the ones they do not use
+    given: Vec<&'static str>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self {
+            day: None,
+            parts: Part::BOTH.to_vec(),
+            input: None,
+            answers: None,
+            iterations: 10,
+            given: Vec::new(),
+        };
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--part" | "-p" => {
+                    let part = match next_value(&mut iter, arg)?.as_str() {
+                        "1" => Part::One,
+                        "2" => Part::Two,
+                        other => return Err(format!("invalid part: {}", other)),
+                    };
+                    options.parts = vec![part];
+                    options.given.push("--part");
+                }
+                "--input" | "-i" => {
+                    options.input = Some(next_value(&mut iter, arg)?.clone());
+                    options.given.push("--input");
+                }
+                "--answers" | "-a" => {
+                    options.answers = Some(next_value(&mut iter, arg)?.clone());
+                    options.given.push("--answers");
+                }
+                "--iterations" | "-n" => {
+                    options.iterations = next_value(&mut iter, arg)?
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| "iterations must be a positive integer".to_string())?;
+                    options.given.push("--iterations");
+                }
+                _ if options.day.is_none() && !arg.starts_with('-') => {
+                    let number = arg
+                        .parse::<u8>()
+                        .map_err(|_| format!("invalid day: {}", arg))?;
+                    let day = aoc_2021::day(number)
+                        .ok_or_else(|| format!("day {} has not been solved", number))?;
+                    options.day = Some(day);
+                    options.given.push("a day");
+                }
+                _ => return Err(format!("unexpected argument: {}", arg)),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Checks that the command uses every option that was passed
+    fn check(&self, command: &str, uses: &[&str]) -> Result<(), String> {
+        match self.given.iter().find(|option| !uses.contains(option)) {
+            Some(option) => Err(format!("{} does not take {}", command, option)),
+            None => Ok(()),
+        }
+    }
+
+    fn day(&self) -> Result<&'static Day, String> {
+        self.day.ok_or_else(|| "expected a day".to_string())
+    }
+
+    fn load_input(&self) -> Result<Vec<String>, String> {
+        match self.input.as_deref() {
+            Some("-") => utils::load_stdin().map_err(|e| format!("could not read stdin: {}", e)),
+            Some(path) => {
+                utils::load_lines(path).map_err(|e| format!("could not load {}: {}", path, e))
+            }
+            None => self.day()?.load_input().map_err(|e| e.to_string()),
+        }
+    }
+}
+
+fn next_value<'a>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<&'a String, String> {
+    iter.next()
+        .ok_or_else(|| format!("expected a value for {}", flag))
+}
+
+fn header(day: &Day) {
+    println!("day {:02}: {}", day.number, day.title);
+}
+
+fn print_report(report: &Report) {
+    for answer in &report.answers {
+        solution::print_answer(answer.part, &answer.value);
+    }
+
+    let timings = report
+        .answers
+        .iter()
+        .map(|answer| format!("part {} {:.2?}", answer.part.number(), answer.elapsed))
+        .collect::<Vec<String>>()
+        .join(", ");
+    println!("time: parse {:.2?}, {}", report.parse, timings);
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let day = options.day()?;
+    let report = day
+        .execute(options.load_input()?, &options.parts)
+        .map_err(|e| e.to_string())?;
+
+    header(day);
+    print_report(&report);
+    Ok(())
+}
+
+fn test_example(options: &Options) -> Result<(), String> {
+    let day = options.day()?;
+    let path = solution::example_path(day.number);
+    let lines = utils::load_lines(&path).map_err(|e| format!("could not load {}: {}", path, e))?;
+    let report = day
+        .execute(lines, &options.parts)
+        .map_err(|e| e.to_string())?;
+
+    header(day);
+    print_report(&report);
+    Ok(())
+}
+
+fn bench(options: &Options) -> Result<(), String> {
+    let day = options.day()?;
+    let lines = options.load_input()?;
+    let iterations = options.iterations;
+
+    let mut parse = Duration::ZERO;
+    let mut parts = vec![Duration::ZERO; options.parts.len()];
+    for _ in 0..iterations {
+        let report = day
+            .execute(lines.clone(), &options.parts)
+            .map_err(|e| e.to_string())?;
+        parse += report.parse;
+        for (total, answer) in parts.iter_mut().zip(&report.answers) {
+            *total += answer.elapsed;
+        }
+    }
+
+    header(day);
+    println!("mean of {} iterations", iterations);
+    println!("parse: {:.2?}", parse / iterations as u32);
+    for (part, total) in options.parts.iter().zip(parts) {
+        println!("part {}: {:.2?}", part.number(), total / iterations as u32);
+    }
+    Ok(())
+}
+
+/// Renders an answer so that it fits on a single row of the summary table
+fn summarise(value: &str) -> String {
+    match value.lines().count() {
+        0 | 1 => value.to_string(),
+        n => format!("<{} lines>", n),
+    }
+}
+
+fn all() -> Result<(), String> {
+    let mut rows: Vec<[String; 6]> = Vec::new();
+    let mut multi_line: Vec<(u8, Part, String)> = Vec::new();
+    let mut failures = 0;
+    let mut total = Duration::ZERO;
+
+    for day in DAYS.iter() {
+        let result = day
+            .load_input()
+            .and_then(|lines| day.execute(lines, &Part::BOTH));
+
+        let report = match result {
+            Ok(report) => report,
+            Err(e) => {
+                failures += 1;
+                rows.push([
+                    format!("{:02}", day.number),
+                    day.title.to_string(),
+                    format!("error: {}", e),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ]);
+                continue;
+            }
+        };
+
+        total += report.elapsed();
+        let mut row = [
+            format!("{:02}", day.number),
+            day.title.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ];
+        for (i, answer) in report.answers.iter().enumerate() {
+            row[2 + i * 2] = summarise(&answer.value);
+            row[3 + i * 2] = format!("{:.2?}", answer.elapsed);
+            if answer.value.contains('\n') {
+                multi_line.push((day.number, answer.part, answer.value.clone()));
+            }
+        }
+        rows.push(row);
+    }
+
+    let titles = ["day", "title", "part 1", "time", "part 2", "time"];
+    let mut widths = titles.map(|title| title.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let render = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{}", render(&titles.map(|title| title.to_string())));
+    println!("{}", widths.map(|width| "-".repeat(width)).join("-|-"));
+    for row in &rows {
+        println!("{}", render(row));
+    }
+    println!("\ntotal time: {:.2?}", total);
+
+    for (number, part, value) in multi_line {
+        println!("\nday {:02} part {}:\n{}", number, part.number(), value);
+    }
+
+    if failures > 0 {
+        return Err(format!("{} day(s) failed", failures));
+    }
+
+    Ok(())
+}
+
+fn verify(options: &Options) -> Result<(), String> {
+    let path = options.answers.as_deref().unwrap_or(answers::ANSWERS_PATH);
+    let manifest = Manifest::load(path).map_err(|e| format!("could not load {}: {}", path, e))?;
+    let days: Vec<&Day> = match options.day {
+        Some(day) => vec![day],
+        None => DAYS.iter().collect(),
+    };
+
+    let mut matched = 0;
+    let mut unrecorded = 0;
+    let mut failures = 0;
+    for day in days {
+        let result = day
+            .load_input()
+            .and_then(|lines| day.execute(lines, &options.parts));
+
+        let report = match result {
+            Ok(report) => report,
+            Err(e) => {
+                failures += 1;
+                println!("day {:02}: error: {}", day.number, e);
+                continue;
+            }
+        };
+
+        for answer in &report.answers {
+            let label = format!("day {:02} part {}", day.number, answer.part.number());
+            match manifest.check(day.number, answer.part, &answer.value) {
+                Verdict::Match => {
+                    matched += 1;
+                    println!("{}: ok", label);
+                }
+                Verdict::Mismatch { expected } => {
+                    failures += 1;
+                    println!("{}: MISMATCH", label);
+                    println!("expected:{}", indent(&expected));
+                    println!("actual:{}", indent(&answer.value));
+                }
+                Verdict::Unrecorded => {
+                    unrecorded += 1;
+                    println!("{}: no recorded answer", label);
+                }
+            }
+        }
+    }
+
+    println!(
+        "\n{} ok, {} failed, {} unrecorded",
+        matched, failures, unrecorded
+    );
+
+    if failures > 0 {
+        return Err(format!("{} answer(s) failed verification", failures));
+    }
+
+    Ok(())
+}
+
+/// Puts multi-line values on the lines after their label, and single-line values after a space
+fn indent(value: &str) -> String {
+    if value.contains('\n') {
+        format!("\n{}", value)
+    } else {
+        format!(" {}", value)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    let uses: &[&str] = match command {
+        "run" => &["a day", "--part", "--input"],
+        "bench" => &["a day", "--part", "--input", "--iterations"],
+        "test-example" => &["a day", "--part"],
+        "all" | "help" | "--help" | "-h" => &[],
+        "verify" => &["a day", "--part", "--answers"],
+        _ => {
+            eprintln!("unknown command: {}\n\n{}", command, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let options = match Options::parse(rest).and_then(|options| {
+        options.check(command, uses)?;
+        Ok(options)
+    }) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let result = match command {
+        "run" => run(&options),
+        "bench" => bench(&options),
+        "test-example" => test_example(&options),
+        "all" => all(),
+        "verify" => verify(&options),
+        _ => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,159 @@
+/// A read position in a sequence of bits, which are packed most significant first into 64 bit
+/// words. Reads past the end return `None` and leave the position unchanged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitCursor<W> {
+    words: W,
+    len: usize,
+    position: usize,
+}
+
+impl<W> BitCursor<W>
+where
+    W: AsRef<[u64]>,
+{
+    /// Creates a cursor at the start of the first `len` bits of the words
+    pub fn new(words: W, len: usize) -> Self {
+        assert!(len <= words.as_ref().len() * 64, "{} bits do not fit in the words", len);
+        Self {
+            words,
+            len,
+            position: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The number of bits left to read
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.len
+    }
+
+    /// Returns the next `width` bits without moving, for widths up to 64
+    pub fn peek(&self, width: usize) -> Option<u64> {
+        if width > 64 || width > self.remaining() {
+            return None;
+        }
+        if width == 0 {
+            return Some(0);
+        }
+
+        let words = self.words.as_ref();
+        let i = self.position / 64;
+        let j = self.position % 64;
+
+        // the bits may straddle two words, in which case j > 0
+        let mut value = words[i] << j;
+        if j + width > 64 {
+            value |= words[i + 1] >> (64 - j);
+        }
+
+        Some(value >> (64 - width))
+    }
+
+    /// Reads the next `width` bits, for widths up to 64
+    pub fn read(&mut self, width: usize) -> Option<u64> {
+        let value = self.peek(width)?;
+        self.position += width;
+        Some(value)
+    }
+
+    pub fn read_bit(&mut self) -> Option<bool> {
+        self.read(1).map(|bit| bit == 1)
+    }
+
+    pub fn skip(&mut self, width: usize) -> Option<()> {
+        if width > self.remaining() {
+            return None;
+        }
+
+        self.position += width;
+        Some(())
+    }
+
+    /// Moves to the given position, which can be anywhere up to the end
+    pub fn seek(&mut self, position: usize) -> Option<()> {
+        if position > self.len {
+            return None;
+        }
+
+        self.position = position;
+        Some(())
+    }
+
+    /// Skips forward to the next multiple of `alignment` bits, such as the start of the next byte
+    pub fn align(&mut self, alignment: usize) -> Option<()> {
+        let offset = self.position % alignment;
+        if offset == 0 {
+            return Some(());
+        }
+
+        self.skip(alignment - offset)
+    }
+}
+
+impl BitCursor<[u64; 1]> {
+    /// Creates a cursor over the lowest `width` bits of a single word
+    pub fn from_word(word: u64, width: usize) -> Self {
+        assert!(width <= 64, "{} bits do not fit in a word", width);
+        let word = if width == 0 { 0 } else { word << (64 - width) };
+        Self::new([word], width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_test() {
+        let words = [0xA001_6C88_0162_017C_u64, 0x3686_B18A_3D47_8000_u64];
+        let mut cursor = BitCursor::new(&words[..], 120);
+
+        assert_eq!(cursor.peek(4), Some(0xA));
+        assert_eq!(cursor.read(16), Some(0xA001));
+        assert_eq!(cursor.position(), 16);
+        assert_eq!(cursor.read(44), Some(0x06C8_8016_2017));
+        assert_eq!(cursor.read(8), Some(0xC3));
+        assert_eq!(cursor.read(64), None);
+        assert_eq!(cursor.remaining(), 52);
+        assert_eq!(cursor.read(52), Some(0x0006_86B1_8A3D_4780));
+        assert!(cursor.is_at_end());
+        assert_eq!(cursor.read_bit(), None);
+
+        cursor.seek(0).unwrap();
+        assert_eq!(cursor.read(64), Some(words[0]));
+        assert_eq!(cursor.seek(121), None);
+    }
+
+    #[test]
+    fn skip_and_align_test() {
+        let mut cursor = BitCursor::from_word(0b1011_0110, 8);
+
+        assert_eq!(cursor.read_bit(), Some(true));
+        cursor.skip(2).unwrap();
+        assert_eq!(cursor.read(2), Some(0b10));
+        cursor.align(4).unwrap();
+        assert_eq!(cursor.position(), 8);
+        assert_eq!(cursor.align(4), Some(()));
+        assert_eq!(cursor.skip(1), None);
+
+        let mut cursor = BitCursor::from_word(0b101, 3);
+        cursor.skip(1).unwrap();
+        assert_eq!(cursor.align(4), None);
+        assert_eq!(cursor.position(), 1);
+    }
+}
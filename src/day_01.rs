@@ -1,15 +1,52 @@
-pub fn part_one(lines: &Vec<u64>) -> u64 {
-    lines
+use crate::{
+    error::{self, AocError},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "sonar sweep";
+
+    type Input = Vec<u64>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(depths: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(depths)
+    }
+
+    fn part_two(depths: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(depths)
+    }
+}
+
+pub fn parse_input(lines: Vec<String>) -> Result<Vec<u64>, AocError> {
+    error::parse_lines(&lines, |x| {
+        x.parse::<u64>()
+            .map_err(|_| AocError::parse("could not parse invalid integer string", x))
+    })
+}
+
+pub fn part_one(lines: &[u64]) -> Result<u64, AocError> {
+    let count = lines
         .iter()
         .fold((0, None), |(count, prev), x| match prev {
             Some(prev_val) if x > prev_val => (count + 1, Some(x)),
             _ => (count, Some(x)),
         })
-        .0
+        .0;
+
+    Ok(count)
 }
 
-pub fn part_two(lines: &Vec<u64>) -> u64 {
-    lines
+pub fn part_two(lines: &[u64]) -> Result<u64, AocError> {
+    let count = lines
         .iter()
         .fold((0, None, None, None), |(count, a, b, c), x| {
             match (a, b, c) {
@@ -25,5 +62,7 @@ pub fn part_two(lines: &Vec<u64>) -> u64 {
                 _ => (count, None, None, Some(x)),
             }
         })
-        .0
+        .0;
+
+    Ok(count)
 }
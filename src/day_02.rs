@@ -1,32 +1,86 @@
-pub fn part_one(commands: &Vec<(&str, u64)>) -> u64 {
-    commands
-        .iter()
-        .fold(
-            [0 as u64, 0 as u64],
-            |[pos, depth], (dir, dist)| match *dir {
-                "forward" => [pos + dist, depth],
-                "down" => [pos, depth + dist],
-                "up" => [pos, depth - dist],
-                _ => [pos, depth],
-            },
-        )
-        .iter()
-        .fold(1, |acc, x| acc * x)
+use std::str::FromStr;
+
+use crate::{
+    error::{self, AocError},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "dive";
+
+    type Input = Vec<Command>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(commands: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(commands)
+    }
+
+    fn part_two(commands: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(commands)
+    }
 }
 
-pub fn part_two(commands: &Vec<(&str, u64)>) -> u64 {
-    commands
-        .iter()
-        .fold(
-            ([0 as u64, 0 as u64], 0 as u64),
-            |([pos, depth], aim), (dir, dist)| match *dir {
-                "forward" => ([pos + dist, depth + dist * aim], aim),
-                "down" => ([pos, depth], aim + dist),
-                "up" => ([pos, depth], aim - dist),
-                _ => ([pos, depth], aim),
-            },
-        )
-        .0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Forward(u64),
+    Down(u64),
+    Up(u64),
+}
+
+impl FromStr for Command {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dir, dist) = s
+            .split_once(' ')
+            .ok_or_else(|| AocError::parse("invalid command input", s))?;
+        let dist = dist.parse::<u64>().map_err(|_| {
+            AocError::parse("could not parse invalid integer string", dist)
+                .at_column(dir.len() + 2)
+        })?;
+
+        match dir {
+            "forward" => Ok(Self::Forward(dist)),
+            "down" => Ok(Self::Down(dist)),
+            "up" => Ok(Self::Up(dist)),
+            _ => Err(AocError::parse("invalid command direction", dir).at_column(1)),
+        }
+    }
+}
+
+pub fn parse_input(lines: Vec<String>) -> Result<Vec<Command>, AocError> {
+    error::parse_lines(&lines, Command::from_str)
+}
+
+pub fn part_one(commands: &[Command]) -> Result<u64, AocError> {
+    let [pos, depth] = commands
         .iter()
-        .fold(1, |acc, x| acc * x)
+        .fold([0_u64, 0_u64], |[pos, depth], command| match *command {
+            Command::Forward(dist) => [pos + dist, depth],
+            Command::Down(dist) => [pos, depth + dist],
+            Command::Up(dist) => [pos, depth - dist],
+        });
+
+    Ok(pos * depth)
+}
+
+pub fn part_two(commands: &[Command]) -> Result<u64, AocError> {
+    let ([pos, depth], _) = commands.iter().fold(
+        ([0_u64, 0_u64], 0_u64),
+        |([pos, depth], aim), command| match *command {
+            Command::Forward(dist) => ([pos + dist, depth + dist * aim], aim),
+            Command::Down(dist) => ([pos, depth], aim + dist),
+            Command::Up(dist) => ([pos, depth], aim - dist),
+        },
+    );
+
+    Ok(pos * depth)
 }
@@ -1,17 +1,48 @@
-pub fn part_one(diagnostics: &Vec<u64>) -> u64 {
-    let mut counts: Vec<usize> = Vec::new();
+use crate::{
+    bits::BitCursor,
+    error::{self, AocError},
+    solution::Solution,
+};
 
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "binary diagnostic";
+
+    type Input = Vec<u64>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(diagnostics: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(diagnostics)
+    }
+
+    fn part_two(diagnostics: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(diagnostics)
+    }
+}
+
+pub fn parse_input(lines: Vec<String>) -> Result<Vec<u64>, AocError> {
+    error::parse_lines(&lines, |line| {
+        u64::from_str_radix(line, 2)
+            .map_err(|_| AocError::parse("could not parse invalid binary integer string", line))
+    })
+}
+
+pub fn part_one(diagnostics: &[u64]) -> Result<u64, AocError> {
+    let num_bits = diagnostics.iter().map(|x| get_num_bits(*x)).max().unwrap_or(0);
+
+    // counts of set bits per column, most significant first
+    let mut counts: Vec<usize> = vec![0; num_bits];
     for elem in diagnostics {
-        let mut reading = *elem;
-        let mut index = 0;
-
-        while reading > 0 {
-            if counts.len() <= index {
-                counts.push(0);
-            }
-            counts[index] += (reading % 2) as usize;
-            reading >>= 1;
-            index += 1;
+        let mut reading = BitCursor::from_word(*elem, num_bits);
+        for count in counts.iter_mut() {
+            *count += reading.read(1).unwrap_or(0) as usize;
         }
     }
 
@@ -19,11 +50,11 @@ pub fn part_one(diagnostics: &Vec<u64>) -> u64 {
     let gamma: u64 = counts
         .iter()
         .map(|x| if *x > half_len { 1 } else { 0 })
-        .rfold(0, |acc, x| (acc << 1) + x);
+        .fold(0, |acc, x| (acc << 1) + x);
 
     let epsilon = gamma ^ ((1 << counts.len()) - 1);
 
-    gamma * epsilon
+    Ok(gamma * epsilon)
 }
 
 fn get_num_bits(num: u64) -> usize {
@@ -38,8 +69,8 @@ fn get_num_bits(num: u64) -> usize {
     num_bits
 }
 
-fn get_majority_bit(readings: &Vec<u64>, bit_mask: u64) -> usize {
-    let half_len = (readings.len() + 1) / 2;
+fn get_majority_bit(readings: &[u64], bit_mask: u64) -> usize {
+    let half_len = readings.len().div_ceil(2);
     let mut count: usize = 0;
     for reading in readings {
         let bit = ((reading & bit_mask) / bit_mask) as usize;
@@ -53,11 +84,11 @@ fn get_majority_bit(readings: &Vec<u64>, bit_mask: u64) -> usize {
     }
 }
 
-fn get_minority_bit(readings: &Vec<u64>, bit_mask: u64) -> usize {
+fn get_minority_bit(readings: &[u64], bit_mask: u64) -> usize {
     get_majority_bit(readings, bit_mask) ^ 1
 }
 
-pub fn part_two(diagnostics: &Vec<u64>) -> u64 {
+pub fn part_two(diagnostics: &[u64]) -> Result<u64, AocError> {
     let max_reading = diagnostics.iter().max();
 
     let num_bits = match max_reading {
@@ -66,40 +97,67 @@ pub fn part_two(diagnostics: &Vec<u64>) -> u64 {
     };
 
     if num_bits == 0 {
-        return 0;
+        return Ok(0);
     }
 
-    let mut o2_sift: Vec<u64> = diagnostics.clone();
-    let mut co2_sift: Vec<u64> = diagnostics.clone();
+    let mut o2_sift: Vec<u64> = diagnostics.to_vec();
+    let mut co2_sift: Vec<u64> = diagnostics.to_vec();
 
     let mut bit_mask = 1 << (num_bits - 1);
     while o2_sift.len() > 1 || co2_sift.len() > 1 {
+        if bit_mask == 0 {
+            return Err(AocError::no_solution(
+                "expected a single reading to be left after sifting on every bit",
+            ));
+        }
+
         // filter o2
         if o2_sift.len() > 1 {
             let majority_bit = get_majority_bit(&o2_sift, bit_mask);
-            o2_sift = o2_sift
-                .into_iter()
-                .filter(|x| {
-                    let bit = ((*x & bit_mask) / bit_mask) as usize;
-                    bit == majority_bit
-                })
-                .collect();
+            o2_sift.retain(|x| {
+                let bit = ((*x & bit_mask) / bit_mask) as usize;
+                bit == majority_bit
+            });
         }
 
         // filter co2
         if co2_sift.len() > 1 {
             let minority_bit = get_minority_bit(&co2_sift, bit_mask);
-            co2_sift = co2_sift
-                .into_iter()
-                .filter(|x| {
-                    let bit = ((*x & bit_mask) / bit_mask) as usize;
-                    bit == minority_bit
-                })
-                .collect();
+            co2_sift.retain(|x| {
+                let bit = ((*x & bit_mask) / bit_mask) as usize;
+                bit == minority_bit
+            });
         }
 
         bit_mask >>= 1;
     }
 
-    o2_sift[0] * co2_sift[0]
+    Ok(o2_sift[0] * co2_sift[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<u64> {
+        let lines = [
+            "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
+            "11001", "00010", "01010",
+        ];
+        parse_input(lines.iter().map(|line| line.to_string()).collect()).unwrap()
+    }
+
+    #[test]
+    fn part_one_test() {
+        assert_eq!(part_one(&example()).unwrap(), 198);
+    }
+
+    #[test]
+    fn part_two_test() {
+        assert_eq!(part_two(&example()).unwrap(), 230);
+
+        // repeated readings can never be sifted down to one
+        let repeated = parse_input(vec!["101".to_string(), "101".to_string()]).unwrap();
+        assert!(part_two(&repeated).unwrap_err().is_no_solution());
+    }
 }
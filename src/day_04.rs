@@ -1,4 +1,32 @@
-use std::{collections::{HashMap, HashSet}, convert::TryFrom, num::ParseIntError};
+use std::{collections::{HashMap, HashSet}, convert::TryFrom};
+
+use crate::{
+    error::{self, AocError},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "giant squid";
+
+    type Input = (Vec<i64>, Vec<BingoBoard>);
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one((sequence, boards): &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(sequence, &mut boards.clone())
+    }
+
+    fn part_two((sequence, boards): &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(sequence, &mut boards.clone())
+    }
+}
 
 #[derive(Default, Debug, Copy, Clone)]
 struct BingoCell {
@@ -26,18 +54,17 @@ pub struct BingoBoard {
 }
 
 impl TryFrom<&[String]> for BingoBoard {
-    type Error = ParseIntError;
+    type Error = AocError;
 
     fn try_from(lines: &[String]) -> Result<Self, Self::Error> {
-        let board: Vec<Vec<i64>> = lines
-            .iter()
-            .map(|line| {
-                line.trim()
-                    .split_whitespace()
-                    .map(|x| x.parse())
-                    .collect::<Result<Vec<i64>, ParseIntError>>()
-            })
-            .collect::<Result<_, ParseIntError>>()?;
+        let board: Vec<Vec<i64>> = error::parse_lines(lines, |line| {
+            line.split_whitespace()
+                .map(|x| {
+                    x.parse()
+                        .map_err(|_| AocError::parse("could not parse integer string", x))
+                })
+                .collect()
+        })?;
 
         Ok(board.into())
     }
@@ -86,7 +113,7 @@ impl BingoBoard {
             .values_to_positions
             .get(&value)
             .cloned()
-            .unwrap_or(Vec::new());
+            .unwrap_or_default();
 
         for (i, j) in &positions {
             self.mark_cell(*i, *j);
@@ -130,7 +157,34 @@ impl BingoBoard {
     }
 }
 
-pub fn part_one(sequence: &Vec<i64>, boards: &mut Vec<BingoBoard>) -> Result<i64, String> {
+/// Parses the drawn number sequence followed by the bingo boards, each section separated by an
+/// empty line
+pub fn parse_input(lines: Vec<String>) -> Result<(Vec<i64>, Vec<BingoBoard>), AocError> {
+    let mut sections = lines.split(|line| line.is_empty());
+
+    let sequence = sections
+        .next()
+        .and_then(|section| section.first())
+        .ok_or_else(|| AocError::parse("expected number sequence", "").at_line(1))?
+        .split(',')
+        .map(|x| {
+            x.parse::<i64>()
+                .map_err(|_| AocError::parse("could not parse integer string", x).at_line(1))
+        })
+        .collect::<Result<Vec<i64>, AocError>>()?;
+
+    // the sequence and the empty line after it
+    let mut offset = 2;
+    let mut boards = Vec::new();
+    for section in sections {
+        boards.push(BingoBoard::try_from(section).map_err(|e| e.offset_line(offset))?);
+        offset += section.len() + 1;
+    }
+
+    Ok((sequence, boards))
+}
+
+pub fn part_one(sequence: &[i64], boards: &mut [BingoBoard]) -> Result<i64, AocError> {
     for value in sequence {
         for board in boards.iter_mut() {
             let positions = board.mark_cells_with_value(*value);
@@ -144,10 +198,10 @@ pub fn part_one(sequence: &Vec<i64>, boards: &mut Vec<BingoBoard>) -> Result<i64
     }
 
 
-    Err("no winning board".to_string())
+    Err(AocError::no_solution("no winning board"))
 }
 
-pub fn part_two(sequence: &Vec<i64>, boards: &mut Vec<BingoBoard>) -> Result<i64, String> {
+pub fn part_two(sequence: &[i64], boards: &mut [BingoBoard]) -> Result<i64, AocError> {
     let mut ongoing_board_indices: HashSet<usize> = HashSet::new();
     for i in 0..boards.len() {
         ongoing_board_indices.insert(i);
@@ -169,7 +223,7 @@ pub fn part_two(sequence: &Vec<i64>, boards: &mut Vec<BingoBoard>) -> Result<i64
     }
 
 
-    Err("no winning board".to_string())
+    Err(AocError::no_solution("no winning board"))
 }
 
 #[cfg(test)]
@@ -1,9 +1,33 @@
-use std::{
-    collections::HashMap,
-    convert::{TryFrom, TryInto},
-    num::ParseIntError,
+use std::{collections::HashMap, convert::TryFrom};
+
+use crate::{
+    error::{self, AocError},
+    solution::Solution,
 };
 
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "hydrothermal venture";
+
+    type Input = Vec<Vent>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(vents: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(vents)
+    }
+
+    fn part_two(vents: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(vents)
+    }
+}
+
 #[derive(Debug, Copy, Default, Clone, PartialEq, Eq, Hash)]
 pub struct IntegerPoint {
     x: i64,
@@ -36,25 +60,36 @@ pub struct Vent {
 }
 
 impl TryFrom<String> for Vent {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(line: String) -> Result<Self, Self::Error> {
+        Self::try_from(line.as_str())
+    }
+}
+
+impl TryFrom<&str> for Vent {
+    type Error = AocError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
         let mut parts = line.split(" -> ");
         let mut points: Vec<IntegerPoint> = Vec::new();
+        let mut column = 1;
         for _ in 0..2 {
-            let values = parts
+            let part = parts
                 .next()
-                .ok_or("invalid input")?
-                .split(",")
+                .ok_or_else(|| AocError::parse("expected 2 points", line))?;
+            let values = part
+                .split(',')
                 .map(|x| x.parse::<i64>())
-                .collect::<Result<Vec<i64>, ParseIntError>>()
-                .or(Err("invalid input"))?;
+                .collect::<Result<Vec<i64>, _>>()
+                .map_err(|_| AocError::parse("could not parse point", part).at_column(column))?;
 
             if values.len() != 2 {
-                return Err("invalid input".into());
+                return Err(AocError::parse("expected 2 coordinates", part).at_column(column));
             }
 
             points.push((values[0], values[1]).into());
+            column += part.len() + " -> ".len();
         }
 
         let start = points[0];
@@ -112,16 +147,13 @@ impl Vent {
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Vec<Vent>, String> {
-    lines
-        .into_iter()
-        .map(|line| line.try_into())
-        .collect::<Result<Vec<Vent>, String>>()
+pub fn parse_input(lines: Vec<String>) -> Result<Vec<Vent>, AocError> {
+    error::parse_lines(&lines, |line| Vent::try_from(line))
 }
 
 /// Returns the number of points where at least 2 vent lines overlap. Only consider horizontal
 /// or vertical vents
-pub fn part_one(vents: &Vec<Vent>) -> usize {
+pub fn part_one(vents: &[Vent]) -> Result<usize, AocError> {
     let mut frequencies: HashMap<IntegerPoint, usize> = HashMap::new();
     for vent in vents
         .iter()
@@ -133,11 +165,11 @@ pub fn part_one(vents: &Vec<Vent>) -> usize {
         }
     }
 
-    frequencies.into_iter().filter(|(_, val)| *val > 1).count()
+    Ok(frequencies.into_iter().filter(|(_, val)| *val > 1).count())
 }
 
 /// Returns the number of points where at least 2 vent lines overlap. Considers all vents
-pub fn part_two(vents: &Vec<Vent>) -> usize {
+pub fn part_two(vents: &[Vent]) -> Result<usize, AocError> {
     let mut frequencies: HashMap<IntegerPoint, usize> = HashMap::new();
     for vent in vents {
         for point in vent.integer_points() {
@@ -146,7 +178,7 @@ pub fn part_two(vents: &Vec<Vent>) -> usize {
         }
     }
 
-    frequencies.into_iter().filter(|(_, val)| *val > 1).count()
+    Ok(frequencies.into_iter().filter(|(_, val)| *val > 1).count())
 }
 
 #[cfg(test)]
@@ -295,7 +327,7 @@ mod tests {
         let lines: Vec<String> = input.split("\n").map(|x| x.to_string()).collect();
         let vents = parse_input(lines).expect("could not parse input");
 
-        let actual = part_one(&vents);
+        let actual = part_one(&vents).unwrap();
         let expected = 5;
 
         assert_eq!(actual, expected);
@@ -317,7 +349,7 @@ mod tests {
         let lines: Vec<String> = input.split("\n").map(|x| x.to_string()).collect();
         let vents = parse_input(lines).expect("could not parse input");
 
-        let actual = part_two(&vents);
+        let actual = part_two(&vents).unwrap();
         let expected = 12;
 
         assert_eq!(actual, expected);
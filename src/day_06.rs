@@ -1,5 +1,30 @@
 use std::{collections::HashMap, num::ParseIntError, str::FromStr};
 
+use crate::{error::AocError, solution::Solution};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "lanternfish";
+
+    type Input = School;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(school: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(school)
+    }
+
+    fn part_two(school: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(school)
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Lanternfish {
     timer: i64,
@@ -49,14 +74,18 @@ pub struct School {
 }
 
 impl FromStr for School {
-    type Err = ParseIntError;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens = s.split(",");
-        let fish = tokens
-            .into_iter()
-            .map(|x| Lanternfish::from_str(x))
-            .collect::<Result<Vec<Lanternfish>, ParseIntError>>()?;
+        let mut column = 1;
+        let mut fish = Vec::new();
+        for token in s.split(',') {
+            let lanternfish = Lanternfish::from_str(token).map_err(|_| {
+                AocError::parse("could not parse lanternfish timer", token).at_column(column)
+            })?;
+            fish.push(lanternfish);
+            column += token.len() + 1;
+        }
 
         Ok(School { fish })
     }
@@ -72,17 +101,19 @@ impl School {
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<School, String> {
-    let line = &lines[0];
-    School::from_str(line).or(Err("could not parse input".to_string()))
+pub fn parse_input(lines: Vec<String>) -> Result<School, AocError> {
+    let line = lines
+        .first()
+        .ok_or_else(|| AocError::parse("expected a line of timers", "").at_line(1))?;
+    School::from_str(line).map_err(|e| e.at_line(1))
 }
 
-pub fn part_one(school: &School) -> i64 {
-    school.simulate(80)
+pub fn part_one(school: &School) -> Result<i64, AocError> {
+    Ok(school.simulate(80))
 }
 
-pub fn part_two(school: &School) -> i64 {
-    school.simulate(256)
+pub fn part_two(school: &School) -> Result<i64, AocError> {
+    Ok(school.simulate(256))
 }
 
 #[cfg(test)]
@@ -197,5 +228,10 @@ mod tests {
         let actual = parse_input(lines);
 
         assert!(actual.is_err());
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "parse error at line 1, column 7: could not parse lanternfish timer in \"foo\""
+        );
+        assert!(parse_input(vec![]).is_err());
     }
 }
@@ -1,5 +1,30 @@
 use std::{num::ParseIntError, str::FromStr};
 
+use crate::{error::AocError, solution::Solution};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "the treachery of whales";
+
+    type Input = CrabFleet;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(fleet: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(fleet)
+    }
+
+    fn part_two(fleet: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(fleet)
+    }
+}
+
 pub enum FuelCost {
     Constant,
     Linear,
@@ -42,14 +67,18 @@ pub struct CrabFleet {
 }
 
 impl FromStr for CrabFleet {
-    type Err = ParseIntError;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens = s.split(",");
-        let fleet = tokens
-            .into_iter()
-            .map(|x| CrabSubmarine::from_str(x))
-            .collect::<Result<Vec<CrabSubmarine>, ParseIntError>>()?;
+        let mut column = 1;
+        let mut fleet = Vec::new();
+        for token in s.split(',') {
+            let sub = CrabSubmarine::from_str(token).map_err(|_| {
+                AocError::parse("could not parse crab position", token).at_column(column)
+            })?;
+            fleet.push(sub);
+            column += token.len() + 1;
+        }
 
         Ok(Self { fleet })
     }
@@ -68,7 +97,10 @@ impl CrabFleet {
     fn align_constant_fuel_cost(&self) -> i64 {
         let mut sorted = self.fleet.clone();
         sorted.sort();
-        let min_x = sorted[0].x;
+        let min_x = match sorted.first() {
+            Some(val) => val.x,
+            None => return 0,
+        };
         let len = sorted.len();
 
         let mut acc: i64 = sorted.iter().map(|sub| sub.x - min_x).sum();
@@ -109,17 +141,19 @@ impl CrabFleet {
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<CrabFleet, String> {
-    let line = &lines[0];
-    CrabFleet::from_str(line).or_else(|_| Err("could not parse input".to_string()))
+pub fn parse_input(lines: Vec<String>) -> Result<CrabFleet, AocError> {
+    let line = lines
+        .first()
+        .ok_or_else(|| AocError::parse("expected a line of crab positions", "").at_line(1))?;
+    CrabFleet::from_str(line).map_err(|e| e.at_line(1))
 }
 
-pub fn part_one(fleet: &CrabFleet) -> i64 {
-    fleet.align(FuelCost::Constant)
+pub fn part_one(fleet: &CrabFleet) -> Result<i64, AocError> {
+    Ok(fleet.align(FuelCost::Constant))
 }
 
-pub fn part_two(fleet: &CrabFleet) -> i64 {
-    fleet.align(FuelCost::Linear)
+pub fn part_two(fleet: &CrabFleet) -> Result<i64, AocError> {
+    Ok(fleet.align(FuelCost::Linear))
 }
 
 #[cfg(test)]
@@ -224,5 +258,6 @@ mod tests {
 
         let actual = parse_input(input);
         assert!(actual.is_err());
+        assert!(parse_input(vec![]).is_err());
     }
 }
@@ -4,6 +4,34 @@ use std::{
     str::FromStr,
 };
 
+use crate::{
+    error::{self, AocError},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "seven segment search";
+
+    type Input = Vec<SSDisplay>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(displays: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(displays)
+    }
+
+    fn part_two(displays: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(displays)
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Segment {
     letter: char,
@@ -27,7 +55,7 @@ pub struct SSDigit {
 
 impl From<&str> for SSDigit {
     fn from(s: &str) -> Self {
-        let segments = s.chars().map(|x| Segment::from(x)).collect();
+        let segments = s.chars().map(Segment::from).collect();
         let mut chars = Vec::from_iter(s.chars());
         chars.sort();
         let sorted_string = chars.into_iter().collect();
@@ -40,10 +68,7 @@ impl From<&str> for SSDigit {
 
 impl SSDigit {
     pub fn has_unique_segment_length(&self) -> bool {
-        match self.segments.len() {
-            2 | 3 | 4 | 7 => true,
-            _ => false,
-        }
+        matches!(self.segments.len(), 2 | 3 | 4 | 7)
     }
 
     pub fn segment_length(&self) -> usize {
@@ -69,12 +94,12 @@ pub struct SSDisplay {
 }
 
 impl FromStr for SSDisplay {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (part1, part2) = s
             .split_once(" | ")
-            .ok_or_else(|| "invalid input".to_string())?;
+            .ok_or_else(|| AocError::parse("expected digits and outputs separated by |", s))?;
         let digits: Vec<SSDigit> = part1.split_whitespace().map(|x| x.into()).collect();
         let outputs: Vec<SSDigit> = part2.split_whitespace().map(|x| x.into()).collect();
         Ok(Self { digits, outputs })
@@ -89,7 +114,7 @@ impl SSDisplay {
             .count()
     }
 
-    pub fn deduce(&self) -> Result<Mapping, String> {
+    pub fn deduce(&self) -> Result<Mapping, AocError> {
         let mut display_segments: Vec<Segment> = Vec::new();
         for c in 'a'..='g' {
             display_segments.push(Segment::from(c));
@@ -99,17 +124,17 @@ impl SSDisplay {
             .digits
             .iter()
             .find(|x| x.segment_length() == 2)
-            .ok_or_else(|| "expected digit with segment length 2")?;
+            .ok_or_else(|| AocError::no_solution("expected digit with segment length 2"))?;
         let seven = self
             .digits
             .iter()
             .find(|x| x.segment_length() == 3)
-            .ok_or_else(|| "expected digit with segment length 3")?;
+            .ok_or_else(|| AocError::no_solution("expected digit with segment length 3"))?;
         let four = self
             .digits
             .iter()
             .find(|x| x.segment_length() == 4)
-            .ok_or_else(|| "expected digit with segment length 4")?;
+            .ok_or_else(|| AocError::no_solution("expected digit with segment length 4"))?;
 
         // one
         for display_segment in &mut display_segments {
@@ -171,7 +196,7 @@ impl SSDisplay {
                     let index = acc & 1;
                     let position = *positions[index];
                     arrangement[position] = Some(segment.letter);
-                    acc = acc >> 1;
+                    acc >>= 1;
                     continue;
                 }
 
@@ -183,13 +208,14 @@ impl SSDisplay {
                 arrangement[*positions[1]] = Some(segment.letter);
             }
 
-            let mapping = self.try_create_mapping(&arrangement);
-            if mapping.is_some() {
-                return Ok(mapping.unwrap());
+            if let Some(mapping) = self.try_create_mapping(&arrangement) {
+                return Ok(mapping);
             }
         }
 
-        Err("could not find an arrangement that satisfied".to_string())
+        Err(AocError::no_solution(
+            "could not find an arrangement that satisfied",
+        ))
     }
 
     fn try_create_mapping(&self, arrangement: &Arrangement) -> Option<Mapping> {
@@ -212,7 +238,7 @@ impl SSDisplay {
                 .iter()
                 .map(|x| {
                     arrangement[*x]
-                        .ok_or_else(|| "expected letter to exist in arrangement, got none")
+                        .ok_or("expected letter to exist in arrangement, got none")
                 })
                 .collect();
 
@@ -224,11 +250,10 @@ impl SSDisplay {
             chars.sort();
             let expected_string: String = chars.into_iter().collect();
 
-            if self
+            if !self
                 .digits
                 .iter()
-                .find(|x| x.sorted_string() == expected_string)
-                .is_none()
+                .any(|x| x.sorted_string() == expected_string)
             {
                 return None;
             }
@@ -239,41 +264,38 @@ impl SSDisplay {
         Some(mapping)
     }
 
-    pub fn calculate_output(&self, mapping: &Mapping) -> usize {
+    pub fn calculate_output(&self, mapping: &Mapping) -> Result<usize, AocError> {
         let mut acc = 0;
         for ss_digit in &self.outputs {
-            let digit = mapping
-                .get(&ss_digit.sorted_string())
-                .expect("expected digit string to exist in mapping");
+            let digit = mapping.get(&ss_digit.sorted_string()).ok_or_else(|| {
+                AocError::no_solution("expected digit string to exist in mapping")
+            })?;
             acc = acc * 10 + digit;
         }
-        acc
+        Ok(acc)
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Vec<SSDisplay>, String> {
-    lines
-        .iter()
-        .map(|line| SSDisplay::from_str(line))
-        .collect::<Result<Vec<SSDisplay>, String>>()
+pub fn parse_input(lines: Vec<String>) -> Result<Vec<SSDisplay>, AocError> {
+    error::parse_lines(&lines, SSDisplay::from_str)
 }
 
-pub fn part_one(displays: &Vec<SSDisplay>) -> usize {
-    displays
+pub fn part_one(displays: &[SSDisplay]) -> Result<usize, AocError> {
+    Ok(displays
         .iter()
         .map(|x| x.num_of_unique_segment_length_outputs())
-        .sum()
+        .sum())
 }
 
-pub fn part_two(displays: &Vec<SSDisplay>) -> usize {
+pub fn part_two(displays: &[SSDisplay]) -> Result<usize, AocError> {
     let mut sum = 0;
     for display in displays {
-        let mapping = display.deduce().expect("could not deduce arrangement");
-        let output_number = display.calculate_output(&mapping);
+        let mapping = display.deduce()?;
+        let output_number = display.calculate_output(&mapping)?;
         sum += output_number;
     }
 
-    return sum;
+    Ok(sum)
 }
 
 #[cfg(test)]
@@ -363,7 +385,7 @@ mod tests {
 
         let displays = parse_input(lines).expect("could not parse input");
 
-        assert_eq!(part_one(&displays), 26);
+        assert_eq!(part_one(&displays).unwrap(), 26);
     }
 
     #[test]
@@ -375,7 +397,7 @@ mod tests {
 
         let displays = parse_input(lines).expect("could not parse input");
 
-        assert_eq!(part_two(&displays), 5353);
+        assert_eq!(part_two(&displays).unwrap(), 5353);
     }
 
     #[test]
@@ -395,6 +417,6 @@ mod tests {
 
         let displays = parse_input(lines).expect("could not parse input");
 
-        assert_eq!(part_two(&displays), 61229);
+        assert_eq!(part_two(&displays).unwrap(), 61229);
     }
 }
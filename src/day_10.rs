@@ -1,5 +1,33 @@
 use std::{convert::TryFrom, str::FromStr};
 
+use crate::{
+    error::{self, AocError},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "syntax scoring";
+
+    type Input = Subsystem;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(subsystem: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(subsystem)
+    }
+
+    fn part_two(subsystem: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(subsystem)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct ChunkToken {
     chunk_type: ChunkType,
@@ -7,7 +35,7 @@ pub struct ChunkToken {
 }
 
 impl TryFrom<char> for ChunkToken {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(c: char) -> Result<Self, Self::Error> {
         let chunk_type = match c {
@@ -15,13 +43,13 @@ impl TryFrom<char> for ChunkToken {
             '[' | ']' => ChunkType::Square,
             '{' | '}' => ChunkType::Curly,
             '<' | '>' => ChunkType::Angle,
-            _ => return Err("invalid token".to_string()),
+            _ => return Err(AocError::parse("invalid token", c)),
         };
 
         let chunk_op = match c {
             '(' | '[' | '{' | '<' => ChunkOp::Open,
             ')' | ']' | '}' | '>' => ChunkOp::Close,
-            _ => return Err("invalid token".to_string()),
+            _ => return Err(AocError::parse("invalid token", c)),
         };
 
         Ok(Self {
@@ -100,13 +128,14 @@ pub struct Line {
 }
 
 impl FromStr for Line {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let tokens = s
             .chars()
-            .map(|c| ChunkToken::try_from(c))
-            .collect::<Result<Vec<ChunkToken>, String>>()?;
+            .enumerate()
+            .map(|(i, c)| ChunkToken::try_from(c).map_err(|e| e.at_column(i + 1)))
+            .collect::<Result<Vec<ChunkToken>, AocError>>()?;
 
         Ok(Self { tokens })
     }
@@ -122,7 +151,7 @@ impl Line {
                 continue;
             }
 
-            match stack.pop().and_then(|x| Some(x.is_matching(token))) {
+            match stack.pop().map(|x| x.is_matching(token)) {
                 Some(false) => return Some(*token),
                 None => return None,
                 _ => (),
@@ -141,7 +170,7 @@ impl Line {
                 continue;
             }
 
-            match stack.pop().and_then(|x| Some(x.is_matching(token))) {
+            match stack.pop().map(|x| x.is_matching(token)) {
                 Some(false) => return None,
                 None => return None,
                 _ => (),
@@ -164,13 +193,10 @@ pub struct Subsystem {
 }
 
 impl TryFrom<Vec<String>> for Subsystem {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(input: Vec<String>) -> Result<Self, Self::Error> {
-        let lines = input
-            .iter()
-            .map(|x| Line::from_str(x))
-            .collect::<Result<Vec<Line>, String>>()?;
+        let lines = error::parse_lines(&input, Line::from_str)?;
 
         Ok(Self { lines })
     }
@@ -185,7 +211,7 @@ impl Subsystem {
             .sum()
     }
 
-    pub fn median_completion_score(&self) -> u64 {
+    pub fn median_completion_score(&self) -> Option<u64> {
         let mut scores: Vec<u64> = self
             .lines
             .iter()
@@ -195,20 +221,22 @@ impl Subsystem {
         scores.sort();
 
         // from problem description: there will always be an odd number of scores to consider
-        scores[scores.len() / 2]
+        scores.get(scores.len() / 2).copied()
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Subsystem, String> {
+pub fn parse_input(lines: Vec<String>) -> Result<Subsystem, AocError> {
     Subsystem::try_from(lines)
 }
 
-pub fn part_one(subsystem: &Subsystem) -> u64 {
-    subsystem.total_syntax_error_score()
+pub fn part_one(subsystem: &Subsystem) -> Result<u64, AocError> {
+    Ok(subsystem.total_syntax_error_score())
 }
 
-pub fn part_two(subsystem: &Subsystem) -> u64 {
-    subsystem.median_completion_score()
+pub fn part_two(subsystem: &Subsystem) -> Result<u64, AocError> {
+    subsystem
+        .median_completion_score()
+        .ok_or_else(|| AocError::no_solution("expected at least 1 incomplete line"))
 }
 
 #[cfg(test)]
@@ -403,7 +431,7 @@ mod tests {
         ];
         let subsystem = parse_input(input).expect("could not parse input");
 
-        assert_eq!(part_one(&subsystem), 26397);
+        assert_eq!(part_one(&subsystem).unwrap(), 26397);
     }
 
     #[test]
@@ -422,6 +450,6 @@ mod tests {
         ];
         let subsystem = parse_input(input).expect("could not parse input");
 
-        assert_eq!(part_two(&subsystem), 288957);
+        assert_eq!(part_two(&subsystem).unwrap(), 288957);
     }
 }
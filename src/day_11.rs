@@ -1,107 +1,52 @@
-use std::{convert::TryFrom, collections::HashSet, ops::{Index, IndexMut}};
+use std::{collections::HashSet, convert::TryFrom};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-pub struct Coordinate(i64, i64);
+use crate::{
+    error::AocError,
+    grid::{self, Coordinate, Grid},
+    solution::Solution,
+};
 
-impl From<(usize, usize)> for Coordinate {
-    fn from(coords: (usize, usize)) -> Self {
-        Coordinate(coords.0 as i64, coords.1 as i64)
-    }
-}
-
-impl Coordinate {
-    pub fn north(&self) -> Self {
-        Self(self.0 - 1, self.1)
-    }
-
-    pub fn south(&self) -> Self {
-        Self(self.0 + 1, self.1)
-    }
+pub struct Puzzle;
 
-    pub fn east(&self) -> Self {
-        Self(self.0, self.1 + 1)
-    }
-
-    pub fn west(&self) -> Self {
-        Self(self.0, self.1 - 1)
-    }
+impl Solution for Puzzle {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "dumbo octopus";
 
-    pub fn northeast(&self) -> Self {
-        self.north().east()
-    }
-
-    pub fn northwest(&self) -> Self {
-        self.north().west()
-    }
+    type Input = EnergyMap;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    pub fn southeast(&self) -> Self {
-        self.south().east()
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
     }
 
-    pub fn southwest(&self) -> Self {
-        self.south().west()
+    fn part_one(energy_map: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(energy_map)
     }
 
-    pub fn neighbours(&self) -> [Self; 8] {
-        [
-            self.north(),
-            self.south(),
-            self.east(),
-            self.west(),
-            self.northeast(),
-            self.northwest(),
-            self.southeast(),
-            self.southwest(),
-        ]
+    fn part_two(energy_map: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(energy_map)
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct EnergyMap {
-    grid: Vec<Vec<usize>>,
-    n: usize,
-    m: usize,
+    grid: Grid<usize>,
 }
 
 impl TryFrom<Vec<String>> for EnergyMap {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
-        let grid = lines
-            .iter()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c.to_digit(10) {
-                        Some(digit) => Ok(digit as usize),
-                        None => Err("could not parse digit".to_string()),
-                    })
-                    .collect::<Result<Vec<usize>, String>>()
-            })
-            .collect::<Result<Vec<Vec<usize>>, String>>()?;
-
-        Ok(grid.into())
+        Ok(Self {
+            grid: Grid::parse(&lines, grid::digit)?,
+        })
     }
 }
 
 impl From<Vec<Vec<usize>>> for EnergyMap {
     fn from(grid: Vec<Vec<usize>>) -> Self {
-        let n = grid.len();
-        let m = grid[0].len();
-        Self { grid, n, m }
-    }
-}
-
-impl Index<Coordinate> for EnergyMap {
-    type Output = usize;
-
-    fn index(&self, idx: Coordinate) -> &Self::Output {
-        &self.grid[idx.0 as usize][idx.1 as usize]
-    }
-}
-
-impl IndexMut<Coordinate> for EnergyMap {
-    fn index_mut(&mut self, idx: Coordinate) -> &mut Self::Output {
-        &mut self.grid[idx.0 as usize][idx.1 as usize]
+        Self { grid: grid.into() }
     }
 }
 
@@ -124,7 +69,7 @@ impl EnergyMap {
         loop {
             let num_flashes = self.step();
             step += 1;
-            if num_flashes == self.n * self.m {
+            if num_flashes == self.grid.n * self.grid.m {
                 return step
             }
         }
@@ -133,30 +78,26 @@ impl EnergyMap {
     /// Simulates 1 step, returns the number of flashes that ocurred
     fn step(&mut self) -> usize {
         // first increase energy level of everything by 1
-        for i in 0..self.n {
-            for j in 0..self.m {
-                self.grid[i][j] += 1;
-            }
+        for (_, energy_level) in self.grid.cells_mut() {
+            *energy_level += 1;
         }
 
         // then do the fireworks
         let mut flashed: HashSet<Coordinate> = HashSet::new();
-        for i in 0..self.n {
-            for j in 0..self.m {
-                self.dfs(Coordinate::from((i, j)), &mut flashed)
-            }
+        for coord in self.grid.coords() {
+            self.dfs(coord, &mut flashed)
         }
 
         // lastly set all the flashed coordinates to 0
         for coord in &flashed {
-            self[*coord] = 0;
+            self.grid[*coord] = 0;
         }
 
         flashed.len()
     }
 
     fn dfs(&mut self, coord: Coordinate, flashed: &mut HashSet<Coordinate>) {
-        let energy_level = self[coord];
+        let energy_level = self.grid[coord];
 
         if energy_level <= 9 || flashed.contains(&coord) {
             return;
@@ -164,31 +105,26 @@ impl EnergyMap {
 
         flashed.insert(coord);
 
-        for neighbour in coord.neighbours() {
-            if self.is_in_bounds(neighbour) {
-                self[neighbour] += 1;
-                self.dfs(neighbour, flashed)
-            }
+        let neighbours: Vec<Coordinate> = self.grid.neighbours(coord).collect();
+        for neighbour in neighbours {
+            self.grid[neighbour] += 1;
+            self.dfs(neighbour, flashed)
         }
     }
-
-    fn is_in_bounds(&self, coord: Coordinate) -> bool {
-        (0..self.n as i64).contains(&coord.0) && (0..self.m as i64).contains(&coord.1)
-    }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<EnergyMap, String> {
+pub fn parse_input(lines: Vec<String>) -> Result<EnergyMap, AocError> {
     EnergyMap::try_from(lines)
 }
 
-pub fn part_one(energy_map: &EnergyMap) -> usize {
+pub fn part_one(energy_map: &EnergyMap) -> Result<usize, AocError> {
     let mut cloned = energy_map.clone();
-    cloned.simulate(100)
+    Ok(cloned.simulate(100))
 }
 
-pub fn part_two(energy_map: &EnergyMap) -> usize {
+pub fn part_two(energy_map: &EnergyMap) -> Result<usize, AocError> {
     let mut cloned = energy_map.clone();
-    cloned.find_synchronisation_step()
+    Ok(cloned.find_synchronisation_step())
 }
 
 #[cfg(test)]
@@ -235,7 +171,7 @@ mod tests {
         ];
         let energy_map = parse_input(lines).expect("could not parse input");
 
-        assert_eq!(part_one(&energy_map), 1656);
+        assert_eq!(part_one(&energy_map).unwrap(), 1656);
     }
 
     #[test]
@@ -254,6 +190,6 @@ mod tests {
         ];
         let energy_map = parse_input(lines).expect("could not parse input");
 
-        assert_eq!(part_two(&energy_map), 195);
+        assert_eq!(part_two(&energy_map).unwrap(), 195);
     }
 }
@@ -1,66 +1,70 @@
 use std::{
     collections::{HashMap, HashSet},
+    convert::TryFrom,
+    hash::Hash,
     str::FromStr,
 };
 
+use crate::{
+    error::{self, AocError},
+    search::{self, Path},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "passage pathing";
+
+    type Input = Vec<Edge>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(edges: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(edges)
+    }
+
+    fn part_two(edges: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(edges)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CaveSize {
     Big,
     Small,
 }
 
+/// A cave, which is an index into the names of its cave system so that it is cheap to copy and
+/// hash. Its name can be printed with `CaveSystem::name`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Cave {
-    id: usize,
+    id: u32,
     size: CaveSize,
 }
 
-impl FromStr for Cave {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.is_empty() {
-            return Err("expected string to be not empty".to_string());
-        }
-
-        let size = if s.to_lowercase() == s {
-            CaveSize::Small
-        } else {
-            CaveSize::Big
-        };
-
-        // we just encode each char into 2 digits
-        let mut id = 0;
-        for c in s.chars() {
-            if !('a'..='z').contains(&c) && !('A'..='Z').contains(&c) {
-                return Err("expected chars to be in [a-zA-Z]".to_string());
-            }
-
-            // +1 to avoid leading 0, mapping every char to a number > 0
-            id = id * 100 + (c as usize - 'A' as usize + 1);
-        }
-
-        Ok(Self { id, size })
-    }
-}
-
 impl Cave {
     pub const START: Self = Self {
-        id: 5152335052,
+        id: 0,
         size: CaveSize::Small,
     };
 
     pub const END: Self = Self {
-        id: 374636,
+        id: 1,
         size: CaveSize::Small,
     };
 
     pub fn is_start(&self) -> bool {
-        self.id == 5152335052
+        *self == Self::START
     }
 
     pub fn is_end(&self) -> bool {
-        self.id == 374636
+        *self == Self::END
     }
 
     pub fn is_big(&self) -> bool {
@@ -72,139 +76,661 @@ impl Cave {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub struct Edge(Cave, Cave);
+fn cave_size(name: &str) -> Result<CaveSize, AocError> {
+    if name.is_empty() {
+        return Err(AocError::parse("expected cave name to be not empty", name));
+    }
+
+    if let Some(i) = name.chars().position(|c| !c.is_ascii_alphanumeric()) {
+        return Err(AocError::parse("expected chars to be in [a-zA-Z0-9]", name).at_column(i + 1));
+    }
+
+    if name.chars().any(|c| c.is_ascii_uppercase()) {
+        Ok(CaveSize::Big)
+    } else {
+        Ok(CaveSize::Small)
+    }
+}
+
+/// A passage between two named caves, with an optional weight written as `A-b:5`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Edge(String, String, Option<usize>);
+
+impl Edge {
+    /// The weight of the passage, which is 1 unless given
+    pub fn weight(&self) -> usize {
+        self.2.unwrap_or(1)
+    }
+}
 
 impl FromStr for Edge {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut caves = s
-            .split("-")
-            .map(|x| Cave::from_str(x))
-            .collect::<Result<Vec<Cave>, String>>()?;
-
-        let b = caves
-            .pop()
-            .ok_or_else(|| "expected edge to contain 2 caves".to_string())?;
-        let a = caves
-            .pop()
-            .ok_or_else(|| "expected edge to contain 2 caves".to_string())?;
-        Ok(Self(a, b))
+        let (names, weight) = match s.split_once(':') {
+            Some((names, weight)) => {
+                let weight = weight.parse::<usize>().map_err(|_| {
+                    AocError::parse("expected weight to be a number", s).at_column(names.len() + 2)
+                })?;
+                (names, Some(weight))
+            }
+            None => (s, None),
+        };
+
+        let (a, b) = names
+            .split_once('-')
+            .ok_or_else(|| AocError::parse("expected edge to contain 2 caves", s))?;
+        if let Some(i) = b.find('-') {
+            return Err(
+                AocError::parse("expected edge to contain 2 caves", s).at_column(a.len() + 2 + i)
+            );
+        }
+
+        cave_size(a)?;
+        cave_size(b).map_err(|e| e.offset_column(a.len() + 1))?;
+        Ok(Self(a.to_string(), b.to_string(), weight))
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct CaveSystem {
     edges: Vec<Edge>,
+    names: Vec<String>,
+    caves: HashMap<String, Cave>,
     adj: HashMap<Cave, HashSet<Cave>>,
+    weights: HashMap<(Cave, Cave), usize>,
 }
 
-impl From<Vec<Edge>> for CaveSystem {
-    fn from(edges: Vec<Edge>) -> Self {
-        let mut adj: HashMap<Cave, HashSet<Cave>> = HashMap::new();
+impl TryFrom<Vec<Edge>> for CaveSystem {
+    type Error = AocError;
+
+    fn try_from(edges: Vec<Edge>) -> Result<Self, Self::Error> {
+        let mut cave_system = Self {
+            edges: Vec::new(),
+            names: Vec::new(),
+            caves: HashMap::new(),
+            adj: HashMap::new(),
+            weights: HashMap::new(),
+        };
+        // start and end are interned first, so that their ids are known constants
+        cave_system.intern("start")?;
+        cave_system.intern("end")?;
 
         for edge in &edges {
-            let set = adj.entry(edge.0).or_insert(HashSet::new());
-            set.insert(edge.1);
+            let a = cave_system.intern(&edge.0)?;
+            let b = cave_system.intern(&edge.1)?;
+            cave_system.adj.entry(a).or_default().insert(b);
+            cave_system.adj.entry(b).or_default().insert(a);
+            // if a passage is listed more than once, the last weight is the one that counts
+            cave_system.weights.insert((a, b), edge.weight());
+            cave_system.weights.insert((b, a), edge.weight());
+        }
+
+        cave_system.edges = edges;
+        Ok(cave_system)
+    }
+}
+
+/// The ways that paths through the cave system can be counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counting {
+    /// Walks every path one at a time
+    Exhaustive,
 
-            let set2 = adj.entry(edge.1).or_insert(HashSet::new());
-            set2.insert(edge.0);
+    /// Remembers the number of paths on from each cave and number of visits to every cave so far,
+    /// so that each of those is only explored once
+    Memoised,
+}
+
+impl Counting {
+    pub const ALL: [Counting; 2] = [Counting::Exhaustive, Counting::Memoised];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Counting::Exhaustive => "exhaustive",
+            Counting::Memoised => "memoised",
         }
+    }
+}
+
+/// Memoising is faster on the real input and far faster on systems with many small caves (see
+/// `benches/day_12.rs`), so it is used unless asked otherwise
+impl Default for Counting {
+    fn default() -> Self {
+        Counting::Memoised
+    }
+}
+
+/// The rules for how often each cave may be visited on a path. By default, as in part one, each
+/// small cave may be visited once and big caves any number of times
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisitPolicy {
+    revisited_small_caves: usize,
+    small_cave_visits: u8,
+    big_cave_visits: Option<u8>,
+    start_revisits: u8,
+}
+
+impl VisitPolicy {
+    pub const PART_ONE: Self = Self {
+        revisited_small_caves: 0,
+        small_cave_visits: 1,
+        big_cave_visits: None,
+        start_revisits: 0,
+    };
+
+    /// A single small cave other than start may be visited twice
+    pub const PART_TWO: Self = Self {
+        revisited_small_caves: 1,
+        small_cave_visits: 2,
+        ..Self::PART_ONE
+    };
+
+    /// Allows up to `k` small caves to be visited up to `n` times each, instead of once. Every
+    /// small cave can always be visited once, so an `n` of 0 is taken as 1
+    pub fn revisit_small_caves(mut self, k: usize, n: u8) -> Self {
+        self.revisited_small_caves = k;
+        self.small_cave_visits = n.max(1);
+        self
+    }
+
+    /// Allows each big cave to be visited at most `m` times
+    pub fn cap_big_caves(mut self, m: u8) -> Self {
+        self.big_cave_visits = Some(m);
+        self
+    }
+
+    /// Allows paths to go back through the start up to `times` times. This is its own allowance,
+    /// so it leaves the revisits of other small caves untouched
+    pub fn revisit_start(mut self, times: u8) -> Self {
+        self.start_revisits = times;
+        self
+    }
+}
+
+impl Default for VisitPolicy {
+    fn default() -> Self {
+        Self::PART_ONE
+    }
+}
+
+impl VisitPolicy {
+    /// Whether visits to small caves have to be counted one by one. Otherwise which small caves
+    /// have been visited and how many were visited again is enough to know where a path can go
+    fn counts_small_caves(&self) -> bool {
+        self.small_cave_visits > 2 || self.small_cave_visits == 2 && self.revisited_small_caves > 1
+    }
+
+    /// Whether visits to any cave have to be counted one by one
+    fn counts_visits(&self) -> bool {
+        self.big_cave_visits.is_some() || self.counts_small_caves()
+    }
+}
+
+/// The number of visits to each cave, for walks under policies that need them
+trait Visits: Copy + Eq + Hash {
+    const NONE: Self;
+
+    fn get(&self, cave: Cave) -> u8;
+
+    fn set(&mut self, cave: Cave, visits: u8);
+}
 
-        Self { edges, adj }
+/// Keeps no counts, for policies that do not need them. This keeps walks small, so that many of
+/// them can be remembered when counting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Uncounted;
+
+impl Visits for Uncounted {
+    const NONE: Self = Uncounted;
+
+    fn get(&self, _: Cave) -> u8 {
+        0
     }
+
+    fn set(&mut self, _: Cave, _: u8) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Counted([u8; CaveSystem::MAX_CAVES]);
+
+impl Visits for Counted {
+    const NONE: Self = Counted([0; CaveSystem::MAX_CAVES]);
+
+    fn get(&self, cave: Cave) -> u8 {
+        self.0[cave.id as usize]
+    }
+
+    fn set(&mut self, cave: Cave, visits: u8) {
+        self.0[cave.id as usize] = visits;
+    }
+}
+
+/// A partial path through the cave system, with the small caves visited so far as a bitset.
+/// Visits to each cave are only counted when the policy limits them, so that walks which can go
+/// to the same places are remembered as one when counting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Walk<V> {
+    cave: Cave,
+    visited: u64,
+    revisited_small_caves: u8,
+    start_revisits: u8,
+    visits: V,
 }
 
 impl CaveSystem {
-    pub fn number_of_paths(&self) -> usize {
-        let mut visited: HashMap<Cave, usize> = HashMap::new();
-        self.dfs(Cave::START, &mut visited)
+    /// The most caves that can be tracked while walking the cave system
+    pub const MAX_CAVES: usize = 64;
+
+    /// Gives the cave with the given name an id, if it does not have one yet. Only `MAX_CAVES`
+    /// caves can be tracked when walking, so no more are given ids
+    fn intern(&mut self, name: &str) -> Result<Cave, AocError> {
+        if let Some(cave) = self.caves.get(name) {
+            return Ok(*cave);
+        }
+
+        if self.names.len() == Self::MAX_CAVES {
+            return Err(AocError::invalid(format!(
+                "expected at most {} caves, including start and end",
+                Self::MAX_CAVES
+            )));
+        }
+
+        let cave = Cave {
+            id: self.names.len() as u32,
+            size: if name.chars().any(|c| c.is_ascii_uppercase()) {
+                CaveSize::Big
+            } else {
+                CaveSize::Small
+            },
+        };
+        self.names.push(name.to_string());
+        self.caves.insert(name.to_string(), cave);
+        Ok(cave)
     }
 
-    fn dfs(&self, cur_cave: Cave, visited: &mut HashMap<Cave, usize>) -> usize {
-        if cur_cave.is_end() {
-            return 1;
+    pub fn contains(&self, cave: Cave) -> bool {
+        self.adj.contains_key(&cave)
+    }
+
+    /// Looks up a cave in the system by name
+    pub fn cave(&self, name: &str) -> Option<Cave> {
+        self.caves.get(name).copied().filter(|cave| self.contains(*cave))
+    }
+
+    pub fn name(&self, cave: Cave) -> &str {
+        &self.names[cave.id as usize]
+    }
+
+    pub fn number_of_caves(&self) -> usize {
+        self.adj.len()
+    }
+
+    pub fn number_of_small_caves(&self) -> usize {
+        self.adj.keys().filter(|cave| cave.is_small()).count()
+    }
+
+    /// Renders the cave system as a Graphviz graph. Big caves are drawn as boxes and small caves
+    /// as circles, with the start and end filled in. The caves and passages of a path, if given,
+    /// are drawn in red
+    pub fn to_dot(&self, path: Option<&[Cave]>) -> String {
+        let path = path.unwrap_or(&[]);
+        let on_path: HashSet<Cave> = path.iter().copied().collect();
+        let passages: HashSet<(Cave, Cave)> = path
+            .windows(2)
+            .flat_map(|pair| [(pair[0], pair[1]), (pair[1], pair[0])])
+            .collect();
+
+        // caves are listed in the order they first appear, so the output is stable
+        let mut seen: HashSet<Cave> = HashSet::new();
+        let caves: Vec<Cave> = self
+            .edges
+            .iter()
+            .flat_map(|edge| [self.caves[&edge.0], self.caves[&edge.1]])
+            .filter(|cave| seen.insert(*cave))
+            .collect();
+
+        let mut dot = String::from("graph caves {\n");
+        for cave in caves {
+            let mut attributes = Vec::new();
+            if cave.is_start() {
+                attributes.push("shape=doublecircle, style=filled, fillcolor=palegreen");
+            } else if cave.is_end() {
+                attributes.push("shape=doublecircle, style=filled, fillcolor=lightcoral");
+            } else if cave.is_big() {
+                attributes.push("shape=box");
+            } else {
+                attributes.push("shape=circle");
+            }
+            if on_path.contains(&cave) {
+                attributes.push("color=red, penwidth=2");
+            }
+
+            dot += &format!("    \"{}\" [{}];\n", self.name(cave), attributes.join(", "));
         }
 
-        if *visited.get(&cur_cave).unwrap_or(&0) > 0 {
-            return 0;
+        for Edge(a, b, weight) in &self.edges {
+            dot += &format!("    \"{}\" -- \"{}\"", a, b);
+            let mut attributes = Vec::new();
+            if let Some(weight) = weight {
+                attributes.push(format!("label={}", weight));
+            }
+            if passages.contains(&(self.caves[a], self.caves[b])) {
+                attributes.push("color=red, penwidth=2".to_string());
+            }
+            if !attributes.is_empty() {
+                dot += &format!(" [{}]", attributes.join(", "));
+            }
+            dot += ";\n";
         }
+        dot += "}\n";
+
+        dot
+    }
+
+    pub fn number_of_paths(&self) -> Result<usize, AocError> {
+        self.number_of_paths_with(VisitPolicy::PART_ONE, Counting::default())
+    }
 
-        if cur_cave.is_small() {
-            visited.entry(cur_cave).and_modify(|e| *e += 1).or_insert(1);
+    pub fn number_of_paths_with_more_time(&self) -> Result<usize, AocError> {
+        self.number_of_paths_with(VisitPolicy::PART_TWO, Counting::default())
+    }
+
+    /// Counts the paths from start to end that follow the policy, in the given way. Fails if there
+    /// are too many to count
+    pub fn number_of_paths_with(
+        &self,
+        policy: VisitPolicy,
+        counting: Counting,
+    ) -> Result<usize, AocError> {
+        if policy.counts_visits() {
+            self.count_walks(&policy, counting, Self::start::<Counted>())
+        } else {
+            self.count_walks(&policy, counting, Self::start::<Uncounted>())
         }
+    }
 
-        let mut count = 0;
+    fn count_walks<V: Visits>(
+        &self,
+        policy: &VisitPolicy,
+        counting: Counting,
+        start: Walk<V>,
+    ) -> Result<usize, AocError> {
+        let successors = |walk: &Walk<V>| {
+            let walk = *walk;
+            self.adj[&walk.cave]
+                .iter()
+                .filter_map(move |next| self.step(policy, walk, *next))
+        };
+        let is_goal = |walk: &Walk<V>| walk.cave.is_end();
 
-        let neighbours = &self.adj[&cur_cave];
-        for neighbour in neighbours {
-            count += self.dfs(*neighbour, visited);
+        match counting {
+            Counting::Exhaustive => Ok(search::count_paths(start, successors, is_goal)),
+            Counting::Memoised => search::count_paths_memoised(start, successors, is_goal)
+                .ok_or_else(|| {
+                    AocError::no_solution(format!(
+                        "expected the number of paths to fit in {} bits",
+                        usize::BITS
+                    ))
+                }),
         }
+    }
 
-        visited.entry(cur_cave).and_modify(|e| *e -= 1);
+    /// Finds the path from start to end with the lowest total weight that follows the policy
+    pub fn shortest_path(&self, policy: VisitPolicy) -> Option<Path<Cave, usize>> {
+        if policy.counts_visits() {
+            self.shortest_walk(&policy, Self::start::<Counted>())
+        } else {
+            self.shortest_walk(&policy, Self::start::<Uncounted>())
+        }
+    }
 
-        count
+    fn shortest_walk<V: Visits>(
+        &self,
+        policy: &VisitPolicy,
+        start: Walk<V>,
+    ) -> Option<Path<Cave, usize>> {
+        let path = search::dijkstra(
+            start,
+            |walk| self.weighted_successors(policy, walk),
+            |walk| walk.cave.is_end(),
+        )?;
+        Some(Self::caves(path))
     }
 
-    pub fn number_of_paths_with_more_time(&self) -> usize {
-        let mut visited: HashMap<Cave, usize> = HashMap::new();
-        self.dfs_with_more_time(Cave::START, &mut visited, true)
+    /// Finds the path from start to end with the highest total weight that follows the policy
+    pub fn longest_path(&self, policy: VisitPolicy) -> Option<Path<Cave, usize>> {
+        if policy.counts_visits() {
+            self.longest_walk(&policy, Self::start::<Counted>())
+        } else {
+            self.longest_walk(&policy, Self::start::<Uncounted>())
+        }
     }
 
-    fn dfs_with_more_time(
+    fn longest_walk<V: Visits>(
         &self,
-        cur_cave: Cave,
-        visited: &mut HashMap<Cave, usize>,
-        has_more_time: bool,
-    ) -> usize {
-        if cur_cave.is_end() {
-            return 1;
+        policy: &VisitPolicy,
+        start: Walk<V>,
+    ) -> Option<Path<Cave, usize>> {
+        let path = search::longest_path(
+            start,
+            |walk| self.weighted_successors(policy, walk),
+            |walk| walk.cave.is_end(),
+        )?;
+        Some(Self::caves(path))
+    }
+
+    fn weighted_successors<'a, V: Visits + 'a>(
+        &'a self,
+        policy: &'a VisitPolicy,
+        walk: Walk<V>,
+    ) -> impl Iterator<Item = (Walk<V>, usize)> + 'a {
+        self.adj[&walk.cave].iter().filter_map(move |next| {
+            let weight = self.weights[&(walk.cave, *next)];
+            self.step(policy, walk, *next).map(|next| (next, weight))
+        })
+    }
+
+    fn caves<V>(path: Path<Walk<V>, usize>) -> Path<Cave, usize> {
+        Path {
+            cost: path.cost,
+            nodes: path.nodes.iter().map(|walk| walk.cave).collect(),
         }
+    }
 
-        let mut still_has_more_time = has_more_time;
-        if *visited.get(&cur_cave).unwrap_or(&0) > 0 {
-            if cur_cave.is_start() || !has_more_time {
-                // no time to visit a small cave again
-                return 0;
-            }
+    /// Returns a lazy iterator over every path from start to end that follows the policy and
+    /// passes the filter, as lists of cave names
+    pub fn paths(&self, policy: VisitPolicy, filter: PathFilter) -> Paths<'_> {
+        // only the current path is kept, so its visits can always be counted
+        let start = Self::start::<Counted>();
+        // no path can go through a cave that is not in the system
+        let must_visit: Option<Vec<Cave>> =
+            filter.must_visit.iter().map(|name| self.cave(name)).collect();
+        let stack = match must_visit {
+            Some(_) => vec![(start, self.neighbours(start.cave))],
+            None => Vec::new(),
+        };
 
-            // otherwise we can visit, but we are using up the free time
-            still_has_more_time = false;
+        Paths {
+            cave_system: self,
+            policy,
+            max_len: filter.max_len,
+            must_visit: must_visit.unwrap_or_default(),
+            path: vec![start.cave],
+            stack,
         }
+    }
 
-        if cur_cave.is_small() {
-            visited.entry(cur_cave).and_modify(|e| *e += 1).or_insert(1);
+    fn start<V: Visits>() -> Walk<V> {
+        Walk {
+            cave: Cave::START,
+            visited: 0,
+            revisited_small_caves: 0,
+            start_revisits: 0,
+            visits: V::NONE,
         }
+    }
 
-        let mut count = 0;
+    /// Returns the caves next to the given one, sorted so that paths come out in a stable order
+    fn neighbours(&self, cave: Cave) -> std::vec::IntoIter<Cave> {
+        let mut neighbours: Vec<Cave> = self
+            .adj
+            .get(&cave)
+            .map(|caves| caves.iter().copied().collect())
+            .unwrap_or_default();
+        neighbours.sort_unstable_by_key(|cave| self.name(*cave));
+        neighbours.into_iter()
+    }
 
-        let neighbours = &self.adj[&cur_cave];
-        for neighbour in neighbours {
-            count += self.dfs_with_more_time(*neighbour, visited, still_has_more_time);
+    /// Extends a walk into the next cave, if the policy allows visiting it
+    fn step<V: Visits>(
+        &self,
+        policy: &VisitPolicy,
+        walk: Walk<V>,
+        next: Cave,
+    ) -> Option<Walk<V>> {
+        let mut walk = Walk { cave: next, ..walk };
+        if next.is_start() {
+            if walk.start_revisits >= policy.start_revisits {
+                return None;
+            }
+            walk.start_revisits += 1;
+            return Some(walk);
         }
 
-        visited.entry(cur_cave).and_modify(|e| *e -= 1);
+        if next.is_big() {
+            if let Some(max) = policy.big_cave_visits {
+                let visits = walk.visits.get(next);
+                if visits >= max {
+                    return None;
+                }
+                walk.visits.set(next, visits + 1);
+            }
+            return Some(walk);
+        }
 
-        count
+        let bit = 1 << next.id;
+        let revisit = if policy.counts_small_caves() {
+            let visits = walk.visits.get(next);
+            if visits >= policy.small_cave_visits {
+                return None;
+            }
+            walk.visits.set(next, visits + 1);
+            visits == 1
+        } else {
+            // at most one small cave can be visited twice, so any cave visited before is revisited
+            walk.visited & bit != 0
+        };
+
+        if revisit {
+            // this small cave becomes one of the few that can be visited again
+            if policy.small_cave_visits < 2
+                || usize::from(walk.revisited_small_caves) >= policy.revisited_small_caves
+            {
+                return None;
+            }
+            walk.revisited_small_caves += 1;
+        }
+
+        walk.visited |= bit;
+        Some(walk)
+    }
+}
+
+/// Limits on the paths yielded by `CaveSystem::paths`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PathFilter {
+    max_len: Option<usize>,
+    must_visit: Vec<String>,
+}
+
+impl PathFilter {
+    /// Only allows paths with at most this many passages
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Only allows paths that go through the cave with the given name
+    pub fn must_visit(mut self, name: &str) -> Self {
+        self.must_visit.push(name.to_string());
+        self
+    }
+}
+
+/// A depth first walk of a cave system that yields paths as it reaches the end, holding only the
+/// current path in memory
+pub struct Paths<'a> {
+    cave_system: &'a CaveSystem,
+    policy: VisitPolicy,
+    max_len: Option<usize>,
+    must_visit: Vec<Cave>,
+    path: Vec<Cave>,
+    stack: Vec<(Walk<Counted>, std::vec::IntoIter<Cave>)>,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = Vec<&'a str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (walk, neighbours) = self.stack.last_mut()?;
+            let (walk, next) = match neighbours.next() {
+                Some(next) => (*walk, next),
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                    continue;
+                }
+            };
+
+            // the path already has one more cave than it has passages
+            if self.max_len.is_some_and(|max| self.path.len() > max) {
+                continue;
+            }
+
+            let walk = match self.cave_system.step(&self.policy, walk, next) {
+                Some(walk) => walk,
+                None => continue,
+            };
+
+            self.path.push(next);
+            if next.is_end() {
+                let allowed = self.must_visit.iter().all(|cave| self.path.contains(cave));
+                let cave_system = self.cave_system;
+                let path: Vec<&'a str> =
+                    self.path.iter().map(|cave| cave_system.name(*cave)).collect();
+                self.path.pop();
+                if allowed {
+                    return Some(path);
+                }
+            } else {
+                self.stack.push((walk, self.cave_system.neighbours(next)));
+            }
+        }
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Vec<Edge>, String> {
-    lines
-        .iter()
-        .map(|x| Edge::from_str(x))
-        .collect::<Result<Vec<Edge>, String>>()
+pub fn parse_input(lines: Vec<String>) -> Result<Vec<Edge>, AocError> {
+    error::parse_lines(&lines, Edge::from_str)
 }
 
-pub fn part_one(edges: Vec<Edge>) -> usize {
-    let cave_system = CaveSystem::from(edges);
-    cave_system.number_of_paths()
+/// Builds the cave system, checking that it can be walked
+pub fn cave_system(edges: &[Edge]) -> Result<CaveSystem, AocError> {
+    let cave_system = CaveSystem::try_from(edges.to_vec())?;
+    if !cave_system.contains(Cave::START) {
+        return Err(AocError::no_solution("expected the cave system to have a start"));
+    }
+
+    Ok(cave_system)
 }
 
-pub fn part_two(edges: Vec<Edge>) -> usize {
-    let cave_system = CaveSystem::from(edges);
-    cave_system.number_of_paths_with_more_time()
+pub fn part_one(edges: &[Edge]) -> Result<usize, AocError> {
+    cave_system(edges)?.number_of_paths()
+}
+
+pub fn part_two(edges: &[Edge]) -> Result<usize, AocError> {
+    cave_system(edges)?.number_of_paths_with_more_time()
 }
 
 #[cfg(test)]
@@ -214,104 +740,42 @@ mod tests {
     use super::*;
 
     #[test]
-    fn char_arithmetic_test() {
-        assert_eq!('a' as usize - 'A' as usize, 32);
-        assert_eq!('b' as usize - 'A' as usize, 33);
-        assert_eq!('z' as usize - 'A' as usize, 57);
-        assert_eq!('A' as usize - 'A' as usize, 0);
-        assert_eq!('Z' as usize - 'A' as usize, 25);
-    }
-
-    #[test]
-    fn cave_from_str_test() {
-        assert_eq!(
-            Cave::from_str("start").unwrap(),
-            Cave {
-                id: 5152335052,
-                size: CaveSize::Small
-            }
-        );
-        assert_eq!(
-            Cave::from_str("end").unwrap(),
-            Cave {
-                id: 374636,
-                size: CaveSize::Small
-            }
-        );
-        assert_eq!(
-            Cave::from_str("A").unwrap(),
-            Cave {
-                id: 1,
-                size: CaveSize::Big
-            }
-        );
-        assert_eq!(
-            Cave::from_str("Z").unwrap(),
-            Cave {
-                id: 26,
-                size: CaveSize::Big
-            }
-        );
-        assert_eq!(
-            Cave::from_str("a").unwrap(),
-            Cave {
-                id: 33,
-                size: CaveSize::Small
-            }
-        );
-        assert_eq!(
-            Cave::from_str("z").unwrap(),
-            Cave {
-                id: 58,
-                size: CaveSize::Small
-            }
-        );
-        assert_eq!(
-            Cave::from_str("AZ").unwrap(),
-            Cave {
-                id: 126,
-                size: CaveSize::Big
-            }
-        );
-        assert_eq!(
-            Cave::from_str("az").unwrap(),
-            Cave {
-                id: 3358,
-                size: CaveSize::Small
-            }
-        );
+    fn cave_size_test() {
+        assert_eq!(cave_size("AZ").unwrap(), CaveSize::Big);
+        assert_eq!(cave_size("a2").unwrap(), CaveSize::Small);
+        assert_eq!(cave_size("Tunnel7").unwrap(), CaveSize::Big);
+        assert_eq!(cave_size("start").unwrap(), CaveSize::Small);
 
-        assert!(Cave::from_str("").is_err());
-        assert!(Cave::from_str("123").is_err());
-        assert!(Cave::from_str("a2").is_err());
+        assert!(cave_size("").is_err());
+        assert!(cave_size("a_b").is_err());
+        assert!(cave_size("caf\u{e9}").is_err());
     }
 
     #[test]
     fn edge_from_str_test() {
-        assert_eq!(
-            Edge::from_str("start-A").unwrap(),
-            Edge(
-                Cave::from_str("start").unwrap(),
-                Cave::from_str("A").unwrap()
-            )
-        );
-        assert_eq!(
-            Edge::from_str("dc-end").unwrap(),
-            Edge(
-                Cave::from_str("dc").unwrap(),
-                Cave::from_str("end").unwrap()
-            )
-        );
-        assert_eq!(
-            Edge::from_str("LN-dc").unwrap(),
-            Edge(Cave::from_str("LN").unwrap(), Cave::from_str("dc").unwrap())
-        );
+        let edge = |a: &str, b: &str, weight| Edge(a.to_string(), b.to_string(), weight);
+
+        assert_eq!(Edge::from_str("start-A").unwrap(), edge("start", "A", None));
+        assert_eq!(Edge::from_str("dc-end").unwrap(), edge("dc", "end", None));
+        assert_eq!(Edge::from_str("LN-dc").unwrap(), edge("LN", "dc", None));
+        assert_eq!(Edge::from_str("A-b:5").unwrap(), edge("A", "b", Some(5)));
+        assert_eq!(Edge::from_str("A-b:5").unwrap().weight(), 5);
+        assert_eq!(Edge::from_str("A-b").unwrap().weight(), 1);
 
         assert!(Edge::from_str("aa").is_err());
         assert!(Edge::from_str("").is_err());
         assert!(Edge::from_str("ab-").is_err());
         assert!(Edge::from_str("-").is_err());
         assert!(Edge::from_str("-A").is_err());
+        assert!(Edge::from_str("A-b:").is_err());
+        assert!(Edge::from_str("A-b:x").is_err());
+        assert!(Edge::from_str("A-a_b").is_err());
+        assert_eq!(
+            Edge::from_str("a-b-c").unwrap_err().to_string(),
+            "parse error at column 4: expected edge to contain 2 caves in \"a-b-c\""
+        );
+        assert!(Edge::from_str("a--b").is_err());
+        assert!(Edge::from_str("a-b-c:5").is_err());
     }
 
     #[test]
@@ -325,14 +789,19 @@ mod tests {
             Edge::from_str("A-end").unwrap(),
             Edge::from_str("b-end").unwrap(),
         ];
-        let actual = CaveSystem::from(edges.clone());
+        let actual = CaveSystem::try_from(edges.clone()).unwrap();
 
-        let start = Cave::from_str("start").unwrap();
-        let a = Cave::from_str("A").unwrap();
-        let b = Cave::from_str("b").unwrap();
-        let c = Cave::from_str("c").unwrap();
-        let d = Cave::from_str("d").unwrap();
-        let end = Cave::from_str("end").unwrap();
+        let start = actual.cave("start").unwrap();
+        let a = actual.cave("A").unwrap();
+        let b = actual.cave("b").unwrap();
+        let c = actual.cave("c").unwrap();
+        let d = actual.cave("d").unwrap();
+        let end = actual.cave("end").unwrap();
+        assert_eq!(start, Cave::START);
+        assert_eq!(end, Cave::END);
+        assert!(a.is_big());
+        assert!(b.is_small());
+        assert!(actual.cave("e").is_none());
 
         let mut expected_adj: HashMap<Cave, HashSet<Cave>> = HashMap::new();
         expected_adj.insert(start, HashSet::from_iter(vec![a, b]));
@@ -342,11 +811,30 @@ mod tests {
         expected_adj.insert(d, HashSet::from_iter(vec![b]));
         expected_adj.insert(end, HashSet::from_iter(vec![a, b]));
 
-        let expected = CaveSystem {
-            edges,
-            adj: expected_adj,
+        assert_eq!(actual.edges, edges);
+        assert_eq!(actual.adj, expected_adj);
+        assert_eq!(actual.number_of_small_caves(), 5);
+    }
+
+    #[test]
+    fn max_caves_test() {
+        // a chain of small caves from start to end, with as many caves as asked for in total
+        let chain = |caves: usize| {
+            let mut names = vec!["start".to_string()];
+            names.extend((0..caves - 2).map(|i| format!("c{}", i)));
+            names.push("end".to_string());
+            let lines = names.windows(2).map(|pair| format!("{}-{}", pair[0], pair[1])).collect();
+            parse_input(lines).expect("could not parse the input")
         };
-        assert_eq!(actual, expected);
+
+        let cave_system = CaveSystem::try_from(chain(CaveSystem::MAX_CAVES)).unwrap();
+        assert_eq!(cave_system.number_of_paths().unwrap(), 1);
+        assert_eq!(cave_system.paths(VisitPolicy::PART_ONE, PathFilter::default()).count(), 1);
+
+        for caves in [CaveSystem::MAX_CAVES + 1, 70] {
+            assert!(CaveSystem::try_from(chain(caves)).is_err());
+            assert!(part_one(&chain(caves)).is_err());
+        }
     }
 
     #[test]
@@ -362,7 +850,7 @@ mod tests {
         ];
         let edges = parse_input(lines).expect("could not parse the input");
 
-        assert_eq!(part_one(edges), 10);
+        assert_eq!(part_one(&edges).unwrap(), 10);
     }
 
     #[test]
@@ -389,7 +877,104 @@ mod tests {
         ];
         let edges = parse_input(lines).expect("could not parse the input");
 
-        assert_eq!(part_one(edges), 226);
+        assert_eq!(part_one(&edges).unwrap(), 226);
+    }
+
+    #[test]
+    fn counting_test() {
+        let lines = vec![
+            "dc-end".to_string(),
+            "HN-start".to_string(),
+            "start-kj".to_string(),
+            "dc-start".to_string(),
+            "dc-HN".to_string(),
+            "LN-dc".to_string(),
+            "HN-end".to_string(),
+            "kj-sa".to_string(),
+            "kj-HN".to_string(),
+            "kj-dc".to_string(),
+        ];
+        let edges = parse_input(lines).expect("could not parse the input");
+        let cave_system = cave_system(&edges).unwrap();
+
+        for counting in Counting::ALL {
+            let count = cave_system.number_of_paths_with(VisitPolicy::PART_ONE, counting);
+            assert_eq!(count.unwrap(), 19);
+            let count = cave_system.number_of_paths_with(VisitPolicy::PART_TWO, counting);
+            assert_eq!(count.unwrap(), 103);
+        }
+    }
+
+    #[test]
+    fn visit_policy_test() {
+        let lines = vec![
+            "start-A".to_string(),
+            "start-b".to_string(),
+            "A-c".to_string(),
+            "A-b".to_string(),
+            "b-d".to_string(),
+            "A-end".to_string(),
+            "b-end".to_string(),
+        ];
+        let edges = parse_input(lines).expect("could not parse the input");
+        let cave_system = cave_system(&edges).unwrap();
+        let policies = [
+            (VisitPolicy::PART_ONE.cap_big_caves(0), 1),
+            (VisitPolicy::PART_ONE.cap_big_caves(1), 4),
+            (VisitPolicy::PART_ONE.revisit_start(1), 37),
+            (VisitPolicy::PART_ONE.revisit_start(2), 89),
+            (VisitPolicy::PART_TWO.revisit_start(1), 196),
+            (VisitPolicy::PART_ONE.revisit_small_caves(2, 2), 54),
+            (VisitPolicy::PART_ONE.revisit_small_caves(1, 3).cap_big_caves(3), 53),
+        ];
+
+        // the usual policies only need to know which small caves have been visited
+        assert!(!VisitPolicy::PART_ONE.counts_visits());
+        assert!(!VisitPolicy::PART_TWO.revisit_start(1).counts_visits());
+        assert!(VisitPolicy::PART_ONE.revisit_small_caves(2, 2).counts_visits());
+        assert!(VisitPolicy::PART_ONE.cap_big_caves(1).counts_visits());
+
+        for (policy, expected) in policies {
+            for counting in Counting::ALL {
+                assert_eq!(cave_system.number_of_paths_with(policy, counting).unwrap(), expected);
+            }
+            assert_eq!(cave_system.paths(policy, PathFilter::default()).count(), expected);
+        }
+    }
+
+    #[test]
+    fn weighted_paths_test() {
+        let lines = vec![
+            "start-A:1".to_string(),
+            "start-b:2".to_string(),
+            "A-c:4".to_string(),
+            "A-b:1".to_string(),
+            "b-d:3".to_string(),
+            "A-end:7".to_string(),
+            "b-end:9".to_string(),
+        ];
+        let edges = parse_input(lines).expect("could not parse the input");
+        let weighted = cave_system(&edges).unwrap();
+        let names = |path: Path<Cave, usize>| {
+            let names: Vec<&str> = path.nodes.iter().map(|cave| weighted.name(*cave)).collect();
+            (path.cost, names.join(","))
+        };
+
+        assert_eq!(
+            weighted.shortest_path(VisitPolicy::PART_ONE).map(names),
+            Some((8, "start,A,end".to_string()))
+        );
+        assert_eq!(
+            weighted.longest_path(VisitPolicy::PART_ONE).map(names),
+            Some((19, "start,A,c,A,b,end".to_string()))
+        );
+
+        // unweighted passages count as 1 each
+        let edges = parse_input(vec!["start-A".to_string(), "A-b".to_string(), "A-end".to_string()])
+            .expect("could not parse the input");
+        let unweighted = cave_system(&edges).unwrap();
+        assert_eq!(unweighted.shortest_path(VisitPolicy::PART_ONE).unwrap().cost, 2);
+        assert_eq!(unweighted.longest_path(VisitPolicy::PART_ONE).unwrap().cost, 4);
     }
 
     #[test]
@@ -405,7 +990,7 @@ mod tests {
         ];
         let edges = parse_input(lines).expect("could not parse the input");
 
-        assert_eq!(part_two(edges), 36);
+        assert_eq!(part_two(&edges).unwrap(), 36);
     }
 
     #[test]
@@ -432,6 +1017,94 @@ mod tests {
         ];
         let edges = parse_input(lines).expect("could not parse the input");
 
-        assert_eq!(part_two(edges), 3509);
+        assert_eq!(part_two(&edges).unwrap(), 3509);
+    }
+
+    #[test]
+    fn name_test() {
+        let lines = vec!["start-A".to_string(), "A-zg".to_string(), "zg-end".to_string()];
+        let edges = parse_input(lines).expect("could not parse the input");
+        let first = cave_system(&edges).unwrap();
+        for name in ["start", "end", "A", "zg"] {
+            assert_eq!(first.name(first.cave(name).unwrap()), name);
+        }
+
+        // names belong to their own cave system, so the same name can mean different caves
+        let edges = parse_input(vec!["start-zg".to_string(), "zg-end".to_string()])
+            .expect("could not parse the input");
+        let other = cave_system(&edges).unwrap();
+        assert_eq!(other.name(other.cave("zg").unwrap()), "zg");
+        assert_ne!(other.cave("zg"), first.cave("zg"));
+    }
+
+    #[test]
+    fn to_dot_test() {
+        let lines = vec![
+            "start-A".to_string(),
+            "A-b".to_string(),
+            "b-end".to_string(),
+            "A-end".to_string(),
+        ];
+        let edges = parse_input(lines).expect("could not parse the input");
+        let cave_system = cave_system(&edges).unwrap();
+        let path = ["start", "A", "end"]
+            .iter()
+            .map(|name| cave_system.cave(name).unwrap())
+            .collect::<Vec<Cave>>();
+
+        assert_eq!(
+            cave_system.to_dot(Some(&path)),
+            r#"graph caves {
+    "start" [shape=doublecircle, style=filled, fillcolor=palegreen, color=red, penwidth=2];
+    "A" [shape=box, color=red, penwidth=2];
+    "b" [shape=circle];
+    "end" [shape=doublecircle, style=filled, fillcolor=lightcoral, color=red, penwidth=2];
+    "start" -- "A" [color=red, penwidth=2];
+    "A" -- "b";
+    "b" -- "end";
+    "A" -- "end" [color=red, penwidth=2];
+}
+"#
+        );
+        assert!(!cave_system.to_dot(None).contains("red, penwidth"));
+    }
+
+    #[test]
+    fn paths_test() {
+        let lines = vec![
+            "start-A".to_string(),
+            "start-b".to_string(),
+            "A-c".to_string(),
+            "A-b".to_string(),
+            "b-d".to_string(),
+            "A-end".to_string(),
+            "b-end".to_string(),
+        ];
+        let edges = parse_input(lines).expect("could not parse the input");
+        let cave_system = cave_system(&edges).unwrap();
+        let names = |path: Vec<&str>| path.join(",");
+
+        assert_eq!(cave_system.paths(VisitPolicy::PART_ONE, PathFilter::default()).count(), 10);
+        assert_eq!(cave_system.paths(VisitPolicy::PART_TWO, PathFilter::default()).count(), 36);
+
+        let mut short: Vec<String> = cave_system
+            .paths(VisitPolicy::PART_ONE, PathFilter::default().max_len(2))
+            .map(names)
+            .collect();
+        short.sort();
+        assert_eq!(short, vec!["start,A,end", "start,b,end"]);
+
+        let mut through_c: Vec<String> = cave_system
+            .paths(VisitPolicy::PART_ONE, PathFilter::default().must_visit("c").max_len(5))
+            .map(names)
+            .collect();
+        through_c.sort();
+        assert_eq!(
+            through_c,
+            vec!["start,A,c,A,b,end", "start,A,c,A,end", "start,b,A,c,A,end"]
+        );
+
+        let missing = PathFilter::default().must_visit("zz");
+        assert_eq!(cave_system.paths(VisitPolicy::PART_ONE, missing).count(), 0);
     }
 }
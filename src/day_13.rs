@@ -1,8 +1,37 @@
 use std::{
-    collections::HashSet, convert::TryFrom, fmt::Display, iter::FromIterator,
-    str::FromStr,
+    collections::{HashMap, HashSet}, convert::TryFrom, fmt::Display, io::Write,
+    iter::FromIterator, str::FromStr,
 };
 
+use crate::{
+    error::{self, AocError},
+    image::{BitmapWriter, ImageFormat},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "transparent origami";
+
+    type Input = Manual;
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(manual: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(manual)
+    }
+
+    fn part_two(manual: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(manual)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Axis {
     X,
@@ -18,19 +47,29 @@ impl From<(u64, u64)> for Point {
     }
 }
 
+impl Point {
+    pub fn x(&self) -> u64 {
+        self.0
+    }
+
+    pub fn y(&self) -> u64 {
+        self.1
+    }
+}
+
 impl FromStr for Point {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(",").map(|x| x.parse::<u64>());
-        let x = iter
-            .next()
-            .ok_or_else(|| "expected 2 numbers".to_string())?
-            .or_else(|_| Err("could not parse int".to_string()))?;
-        let y = iter
-            .next()
-            .ok_or_else(|| "expected 2 numbers".to_string())?
-            .or_else(|_| Err("could not parse int".to_string()))?;
+        let (raw_x, raw_y) = s
+            .split_once(',')
+            .ok_or_else(|| AocError::parse("expected 2 numbers", s))?;
+        let x = raw_x
+            .parse::<u64>()
+            .map_err(|_| AocError::parse("could not parse int", raw_x).at_column(1))?;
+        let y = raw_y
+            .parse::<u64>()
+            .map_err(|_| AocError::parse("could not parse int", raw_y).at_column(raw_x.len() + 2))?;
 
         Ok(Self(x, y))
     }
@@ -40,26 +79,26 @@ impl FromStr for Point {
 pub struct Fold(Axis, u64);
 
 impl FromStr for Fold {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tokens: Vec<&str> = s.split_whitespace().collect();
-        if tokens.len() < 3 {
-            return Err("invalid fold input".to_string());
-        }
+        let instruction = s
+            .strip_prefix("fold along ")
+            .ok_or_else(|| AocError::parse("expected fold to start with \"fold along\"", s))?;
+        let column = s.len() - instruction.len() + 1;
 
-        let (a, b) = tokens[2]
-            .split_once("=")
-            .ok_or_else(|| "invalid input".to_string())?;
+        let (a, b) = instruction
+            .split_once('=')
+            .ok_or_else(|| AocError::parse("expected fold to be of the form axis=value", s))?;
 
         let axis = match a {
             "x" => Axis::X,
             "y" => Axis::Y,
-            _ => return Err("invalid fold input".to_string()),
+            _ => return Err(AocError::parse("invalid fold axis", a).at_column(column)),
         };
-        let value = b
-            .parse::<u64>()
-            .or_else(|_| Err("invalid fold input".to_string()))?;
+        let value = b.parse::<u64>().map_err(|_| {
+            AocError::parse("could not parse fold value", b).at_column(column + a.len() + 1)
+        })?;
 
         Ok(Self(axis, value))
     }
@@ -75,6 +114,37 @@ impl Fold {
     }
 }
 
+/// The letters of the font that folded manuals are written in, each 4 cells wide and 6 high
+const GLYPHS: [(char, &str); 17] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// How `Manual::render` draws the dots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// One character per cell, `█` for a dot and `.` for bare paper
+    Blocks,
+
+    /// Braille patterns, each covering 2 by 4 cells, so that large sheets fit on a screen
+    Braille,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Manual {
     dots: HashSet<Point>,
@@ -82,27 +152,22 @@ pub struct Manual {
 }
 
 impl TryFrom<Vec<String>> for Manual {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
         let mut parts_iter = lines.split(|line| line.is_empty());
 
         let part1 = parts_iter
             .next()
-            .ok_or_else(|| "invalid input".to_string())?;
-        let points = part1
-            .iter()
-            .map(|x| Point::from_str(x))
-            .collect::<Result<Vec<Point>, String>>()?;
-        let dots: HashSet<Point> = HashSet::from_iter(points.into_iter());
+            .ok_or_else(|| AocError::parse("expected a section of dots", ""))?;
+        let points = error::parse_lines(part1, Point::from_str)?;
+        let dots: HashSet<Point> = HashSet::from_iter(points);
 
-        let part2 = parts_iter
-            .next()
-            .ok_or_else(|| "invalid input".to_string())?;
-        let folds = part2
-            .iter()
-            .map(|x| Fold::from_str(x))
-            .collect::<Result<Vec<Fold>, String>>()?;
+        let part2 = parts_iter.next().ok_or_else(|| {
+            AocError::parse("expected a blank line followed by folds", "").at_line(part1.len() + 1)
+        })?;
+        let folds = error::parse_lines(part2, Fold::from_str)
+            .map_err(|e| e.offset_line(part1.len() + 1))?;
 
         Ok(Self { dots, folds })
     }
@@ -154,55 +219,187 @@ impl Manual {
         }
     }
 
+    pub fn folds(&self) -> &[Fold] {
+        &self.folds
+    }
+
     pub fn num_dots(&self) -> usize {
         self.dots.len()
     }
-}
 
-impl Display for Manual {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let max_x = self
-            .dots
-            .iter()
-            .max_by(|a, b| a.0.cmp(&b.0))
-            .map(|v| v.0 + 1)
-            .unwrap_or(0);
-        let max_y = self
-            .dots
-            .iter()
-            .max_by(|a, b| a.1.cmp(&b.1))
-            .map(|v| v.1 + 1)
-            .unwrap_or(0);
+    /// Returns the top left and bottom right corners of the smallest box around every dot
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let min_x = self.dots.iter().map(|p| p.0).min()?;
+        let min_y = self.dots.iter().map(|p| p.1).min()?;
+        let max_x = self.dots.iter().map(|p| p.0).max()?;
+        let max_y = self.dots.iter().map(|p| p.1).max()?;
+
+        Some((Point(min_x, min_y), Point(max_x, max_y)))
+    }
+
+    /// Renders the dots as text. With `crop`, only the box around the dots is drawn instead of
+    /// everything from 0,0, and each cell covers a `scale` by `scale` square of the sheet, showing
+    /// a dot if there are any inside it
+    pub fn render(&self, style: Style, crop: bool, scale: u64) -> String {
+        let (width, height, cells) = self.cells(crop, scale);
+
+        let rows: Vec<String> = match style {
+            Style::Blocks => {
+                let cells: HashSet<(u64, u64)> = cells.collect();
+                (0..height)
+                    .map(|y| {
+                        (0..width)
+                            .map(|x| if cells.contains(&(x, y)) { '█' } else { '.' })
+                            .collect()
+                    })
+                    .collect()
+            }
+            Style::Braille => {
+                // the bit for each of the dots in a braille pattern, by row and then column
+                const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+                let mut patterns: HashMap<(u64, u64), u32> = HashMap::new();
+                for (x, y) in cells {
+                    *patterns.entry((x / 2, y / 4)).or_default() |= DOTS[(y % 4) as usize][(x % 2) as usize];
+                }
+
+                (0..height.div_ceil(4))
+                    .map(|y| {
+                        (0..width.div_ceil(2))
+                            .map(|x| {
+                                let pattern = patterns.get(&(x, y)).copied().unwrap_or(0);
+                                std::char::from_u32(0x2800 + pattern).unwrap_or(' ')
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        };
+
+        rows.join("\n")
+    }
 
-        let mut grid = vec![vec!['.'; max_x as usize]; max_y as usize];
-        for p in self.dots.iter() {
-            grid[p.1 as usize][p.0 as usize] = '█';
+    /// Writes the dots as an image, cropped and scaled down as for `render`. The image is written
+    /// a row at a time, so only the dots themselves are held in memory
+    pub fn write_image(
+        &self,
+        out: impl Write,
+        format: ImageFormat,
+        crop: bool,
+        scale: u64,
+    ) -> Result<(), AocError> {
+        let (width, height, cells) = self.cells(crop, scale);
+        let mut cells: Vec<(u64, u64)> = cells.map(|(x, y)| (y, x)).collect();
+        cells.sort_unstable();
+
+        // an image needs at least one pixel, even for a sheet without dots
+        let (width, height) = (width.max(1), height.max(1));
+        let mut writer = BitmapWriter::new(out, format, width as usize, height as usize)?;
+        let mut row = vec![0_u8; writer.stride()];
+        let mut cells = cells.into_iter().peekable();
+        for y in 0..height {
+            row.iter_mut().for_each(|byte| *byte = 0);
+            while let Some((_, x)) = cells.next_if(|(cell_y, _)| *cell_y == y) {
+                row[(x / 8) as usize] |= 0x80 >> (x % 8);
+            }
+            writer.write_row(&row)?;
         }
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    /// Reads the letters spelled out by the dots. Letters start at the left edge and are 4 cells
+    /// wide, with a blank column after each
+    pub fn read_code(&self) -> Result<String, AocError> {
+        let (_, max) = self
+            .bounds()
+            .ok_or_else(|| AocError::no_solution("expected dots to read"))?;
+        if max.1 >= 6 {
+            return Err(AocError::no_solution(format!(
+                "expected letters to be 6 rows high, found {}",
+                max.1 + 1
+            )));
+        }
+
+        (0..=max.0 / 5)
+            .map(|i| {
+                let left = i * 5;
+                if (0..6).any(|y| self.dots.contains(&Point(left + 4, y))) {
+                    return Err(AocError::no_solution(format!(
+                        "expected a blank column after letter {}",
+                        i + 1
+                    )));
+                }
+
+                let glyph: String = (0..6)
+                    .flat_map(|y| (left..left + 4).map(move |x| Point(x, y)))
+                    .map(|p| if self.dots.contains(&p) { '#' } else { '.' })
+                    .collect();
+                GLYPHS
+                    .iter()
+                    .find(|(_, known)| *known == glyph)
+                    .map(|(letter, _)| *letter)
+                    .ok_or_else(|| {
+                        let rows: Vec<&str> = (0..6).map(|y| &glyph[y * 4..y * 4 + 4]).collect();
+                        AocError::no_solution(format!(
+                            "unknown glyph for letter {}:\n{}",
+                            i + 1,
+                            rows.join("\n")
+                        ))
+                    })
+            })
+            .collect()
+    }
 
-        let s = grid
+    /// Returns the width and height of the area to draw in cells, along with the cell of each dot
+    fn cells(&self, crop: bool, scale: u64) -> (u64, u64, impl Iterator<Item = (u64, u64)> + '_) {
+        let scale = scale.max(1);
+        let (origin, width, height) = match self.bounds() {
+            Some((min, max)) => {
+                let origin = if crop { min } else { Point(0, 0) };
+                let width = (max.0 - origin.0) / scale + 1;
+                let height = (max.1 - origin.1) / scale + 1;
+                (origin, width, height)
+            }
+            None => (Point(0, 0), 0, 0),
+        };
+
+        let cells = self
+            .dots
             .iter()
-            .map(|row| row.iter().collect::<String>())
-            .collect::<Vec<String>>()
-            .join("\n");
+            .map(move |p| ((p.0 - origin.0) / scale, (p.1 - origin.1) / scale));
+
+        (width, height, cells)
+    }
+}
 
-        write!(f, "{}", s)
+impl Display for Manual {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Style::Blocks, false, 1))
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Manual, String> {
+pub fn parse_input(lines: Vec<String>) -> Result<Manual, AocError> {
     Manual::try_from(lines)
 }
 
-pub fn part_one(manual: &Manual) -> usize {
+pub fn part_one(manual: &Manual) -> Result<usize, AocError> {
+    if manual.folds.is_empty() {
+        return Err(AocError::no_solution("expected at least 1 fold"));
+    }
+
     let mut clone = manual.clone();
     clone.single_fold(0);
-    clone.num_dots()
+    Ok(clone.num_dots())
 }
 
-pub fn part_two(manual: &Manual) -> String {
+/// Reads the code off the fully folded sheet. If the dots do not spell out letters, as in the
+/// example, the sheet is rendered instead so that it can still be read by eye
+pub fn part_two(manual: &Manual) -> Result<String, AocError> {
     let mut clone = manual.clone();
     clone.multi_fold();
-    clone.to_string()
+    Ok(clone.read_code().unwrap_or_else(|_| clone.to_string()))
 }
 
 #[cfg(test)]
@@ -222,13 +419,26 @@ mod tests {
 
         let actual = parse_input(split_lines(input)).expect("could not parse input");
         let expected = Manual {
-            dots: HashSet::from_iter(vec![Point(6, 10), Point(0, 14), Point(9, 10)].into_iter()),
+            dots: HashSet::from_iter(vec![Point(6, 10), Point(0, 14), Point(9, 10)]),
             folds: vec![Fold(Axis::Y, 7), Fold(Axis::X, 5)],
         };
 
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn point_from_str_test() {
+        assert_eq!(Point::from_str("007,12").unwrap(), Point(7, 12));
+        assert_eq!(
+            Point::from_str("007,abc").unwrap_err().to_string(),
+            "parse error at column 5: could not parse int in \"abc\""
+        );
+        assert_eq!(
+            Point::from_str("x,1").unwrap_err().to_string(),
+            "parse error at column 1: could not parse int in \"x\""
+        );
+    }
+
     #[test]
     fn part_one_test() {
         let input = "6,10
@@ -254,6 +464,113 @@ mod tests {
                      fold along x=5";
         let manual = parse_input(split_lines(input)).expect("could not parse input");
 
-        assert_eq!(part_one(&manual), 17);
+        assert_eq!(part_one(&manual).unwrap(), 17);
+    }
+
+    #[test]
+    fn part_two_test() {
+        let input = "6,10
+                     0,14
+                     9,10
+                     0,3
+                     10,4
+                     4,11
+                     6,0
+                     6,12
+                     4,1
+                     0,13
+                     10,12
+                     3,4
+                     3,0
+                     8,4
+                     1,10
+                     2,14
+                     8,10
+                     9,0
+
+                     fold along y=7
+                     fold along x=5";
+        let manual = parse_input(split_lines(input)).expect("could not parse input");
+
+        // the example folds into a square rather than letters, so it is rendered
+        assert_eq!(part_two(&manual).unwrap(), "█████\n█...█\n█...█\n█...█\n█████");
+    }
+
+    #[test]
+    fn render_test() {
+        let input = "5,6
+                     7,6
+                     6,7
+                     5,8
+                     7,8
+
+                     fold along y=10";
+        let manual = parse_input(split_lines(input)).expect("could not parse input");
+
+        assert_eq!(manual.bounds(), Some((Point(5, 6), Point(7, 8))));
+        assert_eq!(manual.render(Style::Blocks, true, 1), "█.█\n.█.\n█.█");
+        assert_eq!(manual.render(Style::Blocks, true, 2), "██\n██");
+        assert_eq!(manual.render(Style::Blocks, false, 4), "..\n.█\n.█");
+        assert_eq!(manual.render(Style::Blocks, false, 1), manual.to_string());
+        assert_eq!(manual.render(Style::Braille, true, 1), "\u{2815}\u{2805}");
+    }
+
+    #[test]
+    fn write_image_test() {
+        let input = "5,6
+                     7,6
+                     6,7
+                     5,8
+                     7,8
+
+                     fold along y=10";
+        let manual = parse_input(split_lines(input)).expect("could not parse input");
+
+        let mut pbm = Vec::new();
+        manual.write_image(&mut pbm, ImageFormat::Pbm, true, 1).unwrap();
+        let mut expected = b"P4\n3 3\n".to_vec();
+        expected.extend_from_slice(&[0b1010_0000, 0b0100_0000, 0b1010_0000]);
+        assert_eq!(pbm, expected);
+    }
+
+    #[test]
+    fn read_code_test() {
+        let manual = |rows: &[&str]| {
+            let dots = rows
+                .iter()
+                .enumerate()
+                .flat_map(|(y, row)| {
+                    row.chars()
+                        .enumerate()
+                        .filter(|(_, c)| *c == '#')
+                        .map(move |(x, _)| Point(x as u64, y as u64))
+                })
+                .collect();
+            Manual {
+                dots,
+                folds: vec![],
+            }
+        };
+
+        let code = manual(&[
+            "..##..##...##....##.####.####.#..#.#..#",
+            "...#.#..#.#..#....#.#....#....#.#..#..#",
+            "...#.#....#..#....#.###..###..##...#..#",
+            "...#.#.##.####....#.#....#....#.#..#..#",
+            "#..#.#..#.#..#.#..#.#....#....#.#..#..#",
+            ".##...###.#..#..##..####.#....#..#..##.",
+        ]);
+        assert_eq!(code.read_code().unwrap(), "JGAJEFKU");
+
+        let unknown = manual(&["#..#", ".##.", "#..#", ".##.", "#..#", ".##."]);
+        let e = unknown.read_code().unwrap_err().to_string();
+        assert!(e.contains("unknown glyph for letter 1"), "{}", e);
+
+        let joined = manual(&["#####", "#....", "###..", "#....", "#....", "####."]);
+        assert!(joined.read_code().is_err());
+
+        let tall = manual(&["#", ".", ".", ".", ".", ".", "#"]);
+        assert!(tall.read_code().is_err());
+        assert!(manual(&[]).read_code().is_err());
     }
 }
@@ -1,5 +1,33 @@
 use std::{convert::TryFrom, str::FromStr, collections::HashMap};
 
+use crate::{
+    error::{self, AocError},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "extended polymerization";
+
+    type Input = Polymer;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(polymer: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(polymer)
+    }
+
+    fn part_two(polymer: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(polymer)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Element {
     id: u64,
@@ -13,17 +41,23 @@ impl From<char> for Element {
 }
 
 impl FromStr for Element {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 1 {
-            return Err("expected element to be 1 char".to_string());
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::parse(c, 1),
+            _ => Err(AocError::parse("expected element to be 1 char", s)),
         }
+    }
+}
 
-        let c = s
-            .chars()
-            .next()
-            .ok_or_else(|| "expected element to be 1 char".to_string())?;
+impl Element {
+    /// Parses an element, reporting the given column if it is not in [A-Z]
+    fn parse(c: char, column: usize) -> Result<Self, AocError> {
+        if !c.is_ascii_uppercase() {
+            return Err(AocError::parse("expected element to be in [A-Z]", c).at_column(column));
+        }
 
         Ok(Self::from(c))
     }
@@ -37,23 +71,19 @@ pub struct Rule {
 }
 
 impl FromStr for Rule {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (part1, part2) = s
             .split_once(" -> ")
-            .ok_or_else(|| "invalid rule input".to_string())?;
+            .ok_or_else(|| AocError::parse("expected rule to be of the form AB -> C", s))?;
 
         let mut iter = part1.chars();
-        let key: (Element, Element) = (
-            iter.next()
-                .ok_or_else(|| "invalid rule input".to_string())?
-                .into(),
-            iter.next()
-                .ok_or_else(|| "invalid rule input".to_string())?
-                .into(),
-        );
-        let value = Element::from_str(part2)?;
+        let key: (Element, Element) = match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => (Element::parse(a, 1)?, Element::parse(b, 2)?),
+            _ => return Err(AocError::parse("expected rule to have a pair of elements", s)),
+        };
+        let value = Element::from_str(part2).map_err(|e| e.offset_column(part1.len() + 4))?;
         let id = Self::make_id(key.0, key.1);
 
         Ok(Self { id, key, value })
@@ -75,21 +105,29 @@ pub struct Polymer {
 }
 
 impl TryFrom<Vec<String>> for Polymer {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
         let mut iter = lines.iter();
         let part1 = iter
             .next()
-            .ok_or_else(|| "expected polymer template".to_string())?;
-        let template: Vec<Element> = part1.chars().map(|c| Element::from(c)).collect();
+            .ok_or_else(|| AocError::parse("expected polymer template", ""))?;
+        let template = part1
+            .chars()
+            .enumerate()
+            .map(|(i, c)| Element::parse(c, i + 1))
+            .collect::<Result<Vec<Element>, AocError>>()
+            .map_err(|e| e.at_line(1))?;
 
         // empty line
-        iter.next().ok_or_else(|| "invalid input".to_string())?;
+        match iter.next() {
+            Some(line) if line.is_empty() => (),
+            Some(line) => return Err(AocError::parse("expected an empty line", line).at_line(2)),
+            None => return Err(AocError::parse("expected an empty line", "").at_line(2)),
+        }
 
         let mut rules: Vec<Option<Rule>> = vec![None; 2727];
-        for s in iter {
-            let rule = Rule::from_str(s)?;
+        for rule in error::parse_lines(&lines[2..], Rule::from_str).map_err(|e| e.offset_line(2))? {
             rules[rule.id as usize] = Some(rule);
         }
 
@@ -158,18 +196,18 @@ impl Polymer {
     }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Polymer, String> {
+pub fn parse_input(lines: Vec<String>) -> Result<Polymer, AocError> {
     Polymer::try_from(lines)
 }
 
-pub fn part_one(polymer: &Polymer) -> usize {
+pub fn part_one(polymer: &Polymer) -> Result<usize, AocError> {
     let (min, max) = polymer.min_max_after_steps(10);
-    max - min
+    Ok(max - min)
 }
 
-pub fn part_two(polymer: &Polymer) -> usize {
+pub fn part_two(polymer: &Polymer) -> Result<usize, AocError> {
     let (min, max) = polymer.min_max_after_steps(40);
-    max - min
+    Ok(max - min)
 }
 
 #[cfg(test)]
@@ -241,7 +279,7 @@ mod tests {
                     CN -> C";
 
         let polymer = parse_input(split_lines(input)).expect("could not parse input");
-        assert_eq!(part_one(&polymer), 1588);
+        assert_eq!(part_one(&polymer).unwrap(), 1588);
     }
 
     #[test]
@@ -266,6 +304,6 @@ mod tests {
                     CN -> C";
 
         let polymer = parse_input(split_lines(input)).expect("could not parse input");
-        assert_eq!(part_two(&polymer), 2188189693529);
+        assert_eq!(part_two(&polymer).unwrap(), 2188189693529);
     }
 }
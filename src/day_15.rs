@@ -1,17 +1,45 @@
-use std::{collections::BinaryHeap, convert::TryFrom};
+use std::{collections::HashSet, convert::TryFrom, fmt::Display, str::FromStr};
 
-use crate::grid::{Coordinate, Grid};
+use crate::{
+    error::AocError,
+    grid::{Coordinate, Grid},
+    search::{self, Path},
+    solution::Solution,
+};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "chiton";
+
+    type Input = Cavern;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(cavern: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(cavern)
+    }
+
+    fn part_two(cavern: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(cavern)
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Risk(usize);
 
 impl TryFrom<char> for Risk {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(c: char) -> Result<Self, Self::Error> {
         match c.to_digit(10) {
-            Some(digit) => Ok(Risk(digit as usize)),
-            None => Err("could not parse digit".to_string()),
+            Some(digit) if digit > 0 => Ok(Risk(digit as usize)),
+            _ => Err(AocError::parse("expected risk level to be in [1-9]", c)),
         }
     }
 }
@@ -22,85 +50,234 @@ impl From<usize> for Risk {
     }
 }
 
+impl Risk {
+    pub fn value(&self) -> usize {
+        self.0
+    }
+}
+
+/// Computes the risk of a cell in a tile of the cavern, from the risk of the same cell in the
+/// scanned tile and the distance of the tile from the top left one (its row plus its column)
+pub type Wrap = fn(Risk, usize) -> Risk;
+
+/// The puzzle rule: risk goes up by 1 per tile, wrapping from 9 back around to 1
+pub fn wrap_risk(risk: Risk, distance: usize) -> Risk {
+    Risk((risk.0 - 1 + distance) % 9 + 1)
+}
+
+impl Display for Risk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The shortest path algorithms that can be used to find the path with the lowest risk
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct State {
-    cost: usize,
-    coord: Coordinate,
+pub enum Algorithm {
+    Dijkstra,
+
+    /// A* guided by the manhattan distance to the target, scaled by the lowest risk level so
+    /// that it never overestimates
+    AStar,
+
+    /// Dijkstra from both ends at once, meeting in the middle
+    Bidirectional,
+
+    /// Dial's algorithm, which takes advantage of risk levels being small by using a ring of
+    /// buckets instead of a binary heap
+    Bucket,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.cost.cmp(&self.cost)
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [
+        Algorithm::Dijkstra,
+        Algorithm::AStar,
+        Algorithm::Bidirectional,
+        Algorithm::Bucket,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Dijkstra => "dijkstra",
+            Algorithm::AStar => "a*",
+            Algorithm::Bidirectional => "bidirectional",
+            Algorithm::Bucket => "bucket",
+        }
     }
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+/// The bucket queue is used unless another algorithm is asked for. On the tiled real input it
+/// takes about half as long as Dijkstra, and on a generated 1000x1000 cavern about two thirds as
+/// long (see `benches/day_15.rs`). A* only helps on the untiled real input, where all but the
+/// bidirectional search are within a millisecond of each other
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Bucket
     }
 }
 
-impl State {
-    pub fn new(cost: usize, coord: Coordinate) -> Self {
-        Self { cost, coord }
+impl FromStr for Algorithm {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|algorithm| algorithm.name() == s || (s == "astar" && *algorithm == Self::AStar))
+            .ok_or_else(|| AocError::parse("unknown algorithm", s))
     }
 }
 
+/// How the cells on a path are told apart from the rest of the cavern when rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    /// Cells off the path are replaced by dots, which keeps the output plain enough to diff
+    Dots,
+
+    /// Every risk level is kept, and cells on the path are coloured with ANSI escape codes
+    Ansi,
+}
+
 pub struct Cavern {
     grid: Grid<Risk>,
+    tiles: usize,
+    wrap: Wrap,
 }
 
 impl TryFrom<Vec<String>> for Cavern {
-    type Error = String;
+    type Error = AocError;
 
     fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
         Ok(Self {
             grid: Grid::try_from(lines)?,
+            tiles: 5,
+            wrap: wrap_risk,
         })
     }
 }
 
 impl Cavern {
+    /// Sets how many times the scanned tile is repeated in each direction to make up the full
+    /// cavern, which is 5 by default. There must be at least 1 tile
+    pub fn with_tiles(self, tiles: usize) -> Result<Self, AocError> {
+        if tiles == 0 {
+            return Err(AocError::invalid("expected the cavern to have at least 1 tile"));
+        }
+
+        Ok(Self { tiles, ..self })
+    }
+
+    /// Sets how risk levels change from tile to tile, which is `wrap_risk` by default
+    pub fn with_wrap(self, wrap: Wrap) -> Self {
+        Self { wrap, ..self }
+    }
+
+    pub fn tiles(&self) -> usize {
+        self.tiles
+    }
+
     /// Returns the total risk of the path with the lowest risk
     pub fn lowest_risk(&self, target: Coordinate) -> Option<usize> {
-        let start: Coordinate = (0_i64, 0_i64).into();
-        let mut costs: Grid<usize> = Grid::new(self.grid.n * 5, self.grid.m * 5, usize::MAX);
-        let mut q: BinaryHeap<State> = BinaryHeap::new();
+        self.lowest_risk_path(target).map(|path| path.cost)
+    }
 
-        costs[start] = 0;
-        q.push(State::new(0, start));
+    /// Returns the path with the lowest risk from the top left to the target, including both
+    /// ends, along with its total risk
+    pub fn lowest_risk_path(&self, target: Coordinate) -> Option<Path<Coordinate, usize>> {
+        self.lowest_risk_path_with(target, Algorithm::default())
+    }
 
-        while let Some(State { cost, coord }) = q.pop() {
-            // if we are visiting the target cell, then this must be the shortest path
-            if coord == target {
-                return Some(cost);
-            }
+    /// Returns the path with the lowest risk from the top left to the target, found with the
+    /// given algorithm. Every algorithm finds a path with the same total risk, but when several
+    /// paths tie they may not pick the same one
+    pub fn lowest_risk_path_with(
+        &self,
+        target: Coordinate,
+        algorithm: Algorithm,
+    ) -> Option<Path<Coordinate, usize>> {
+        let start: Coordinate = (0_i64, 0_i64).into();
+        if !self.is_in_bounds(target) {
+            return None;
+        }
 
-            // if the current cost is more than the recorded lowest cost, then prune this path
-            if cost > costs[coord] {
-                continue;
+        // the search can wander anywhere in the tiled cavern, and a cell for each coordinate is
+        // much cheaper to look up than a hash map
+        let store = Grid::new(self.grid.n * self.tiles, self.grid.m * self.tiles, None);
+        let neighbours = |coord| self.neighbours(coord);
+        let is_target = |coord| coord == target;
+        match algorithm {
+            Algorithm::Dijkstra => search::dijkstra_in(store, start, neighbours, is_target),
+            Algorithm::AStar => {
+                // every step costs at least the lowest risk, so this never overestimates
+                let (min_risk, _) = self.risk_range();
+                search::astar_in(
+                    store,
+                    start,
+                    neighbours,
+                    |coord: Coordinate| {
+                        let distance = (target.row() - coord.row()).abs()
+                            + (target.col() - coord.col()).abs();
+                        distance as usize * min_risk
+                    },
+                    is_target,
+                )
             }
-
-            for neighbour in coord.cardinal_neighbours() {
-                // skip if neighbour is not in bounds
-                if !self.is_in_bounds(neighbour) {
-                    continue;
-                }
-
-                let next_state = State::new(cost + self.get(neighbour).0, neighbour);
-                if next_state.cost < costs[neighbour] {
-                    q.push(next_state);
-                    costs[neighbour] = next_state.cost;
-                }
+            Algorithm::Bidirectional => search::bidirectional_dijkstra_in(
+                store,
+                start,
+                target,
+                neighbours,
+                |coord| self.predecessors(coord),
+            ),
+            Algorithm::Bucket => {
+                let (_, max_risk) = self.risk_range();
+                search::dial_in(store, start, neighbours, is_target, max_risk)
             }
         }
+    }
+
+    /// Renders the risk levels of the cavern, either as scanned or fully tiled, with the cells of
+    /// the given path highlighted
+    pub fn render(&self, tiled: bool, path: &[Coordinate], highlight: Highlight) -> String {
+        let (n, m) = if tiled {
+            (self.grid.n * self.tiles, self.grid.m * self.tiles)
+        } else {
+            (self.grid.n, self.grid.m)
+        };
+        let on_path: HashSet<Coordinate> = path.iter().copied().collect();
+
+        let rendered: Grid<String> = Grid::from_fn(n, m, |coord| {
+            let risk = self.get(coord);
+            match (on_path.contains(&coord), highlight) {
+                (true, Highlight::Dots) => risk.to_string(),
+                (false, Highlight::Dots) => ".".to_string(),
+                (true, Highlight::Ansi) => format!("\x1b[1;31m{}\x1b[0m", risk),
+                (false, Highlight::Ansi) => risk.to_string(),
+            }
+        });
+
+        rendered.to_string()
+    }
+
+    /// Returns the neighbours of a coordinate within the full (tiled) cavern, along with the risk
+    /// of entering each of them
+    fn neighbours(&self, coord: Coordinate) -> impl Iterator<Item = (Coordinate, usize)> + '_ {
+        IntoIterator::into_iter(coord.cardinal_neighbours())
+            .filter(move |neighbour| self.is_in_bounds(*neighbour))
+            .map(move |neighbour| (neighbour, self.get(neighbour).0))
+    }
 
-        None
+    /// Returns the coordinates that can step into the given one, along with the risk of doing so
+    fn predecessors(&self, coord: Coordinate) -> impl Iterator<Item = (Coordinate, usize)> + '_ {
+        let risk = self.get(coord).0;
+        IntoIterator::into_iter(coord.cardinal_neighbours())
+            .filter(move |neighbour| self.is_in_bounds(*neighbour))
+            .map(move |neighbour| (neighbour, risk))
     }
 
     pub fn is_in_bounds(&self, coord: Coordinate) -> bool {
-        (0..(self.grid.n * 5) as i64).contains(&coord.row())
-            && (0..(self.grid.m * 5) as i64).contains(&coord.col())
+        (0..(self.grid.n * self.tiles) as i64).contains(&coord.row())
+            && (0..(self.grid.m * self.tiles) as i64).contains(&coord.col())
     }
 
     pub fn get(&self, coord: Coordinate) -> Risk {
@@ -112,28 +289,42 @@ impl Cavern {
         let col_remainder = col % self.grid.m;
         let new_coord = Coordinate::from((row_remainder, col_remainder));
         let risk = self.grid[new_coord];
-        let new_risk = Risk((risk.0 - 1 + row_quotient + col_quotient) % 9 + 1);
-        new_risk
+        (self.wrap)(risk, row_quotient + col_quotient)
     }
 
     pub fn southeast_corner(&self) -> Coordinate {
         (self.grid.n - 1, self.grid.m - 1).into()
     }
+
+    /// Returns the southeast corner of the fully tiled cavern
+    pub fn tiled_southeast_corner(&self) -> Coordinate {
+        (self.grid.n * self.tiles - 1, self.grid.m * self.tiles - 1).into()
+    }
+
+    /// Returns the lowest and highest risk levels anywhere in the fully tiled cavern
+    fn risk_range(&self) -> (usize, usize) {
+        let distances = 0..(self.tiles - 1) * 2 + 1;
+        self.grid
+            .values()
+            .flat_map(|risk| distances.clone().map(move |distance| (self.wrap)(*risk, distance).0))
+            .fold((usize::MAX, 0), |(min, max), risk| (min.min(risk), max.max(risk)))
+    }
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Cavern, String> {
+pub fn parse_input(lines: Vec<String>) -> Result<Cavern, AocError> {
     Cavern::try_from(lines)
 }
 
-pub fn part_one(cavern: &Cavern) -> usize {
+pub fn part_one(cavern: &Cavern) -> Result<usize, AocError> {
     cavern
         .lowest_risk(cavern.southeast_corner())
-        .expect("could not find a path")
+        .ok_or_else(|| AocError::no_solution("could not find a path"))
 }
 
-pub fn part_two(cavern: &Cavern) -> usize {
-    let target = Coordinate::from((cavern.grid.n * 5 - 1, cavern.grid.m * 5 - 1));
-    cavern.lowest_risk(target).expect("could not find a path")
+pub fn part_two(cavern: &Cavern) -> Result<usize, AocError> {
+    cavern
+        .lowest_risk(cavern.tiled_southeast_corner())
+        .ok_or_else(|| AocError::no_solution("could not find a path"))
 }
 
 #[cfg(test)]
@@ -178,7 +369,7 @@ mod tests {
                      2311944581";
         let cavern = parse_input(split_lines(input)).expect("could not parse input");
 
-        assert_eq!(part_one(&cavern), 40);
+        assert_eq!(part_one(&cavern).unwrap(), 40);
     }
 
     #[test]
@@ -195,6 +386,167 @@ mod tests {
                      2311944581";
         let cavern = parse_input(split_lines(input)).expect("could not parse input");
 
-        assert_eq!(part_two(&cavern), 315);
+        assert_eq!(part_two(&cavern).unwrap(), 315);
+    }
+
+    #[test]
+    fn lowest_risk_path_test() {
+        let input = "1163751742
+                     1381373672
+                     2136511328
+                     3694931569
+                     7463417111
+                     1319128137
+                     1359912421
+                     3125421639
+                     1293138521
+                     2311944581";
+        let cavern = parse_input(split_lines(input)).expect("could not parse input");
+
+        let path = cavern
+            .lowest_risk_path(cavern.southeast_corner())
+            .expect("could not find a path");
+        assert_eq!(path.cost, 40);
+        assert_eq!(path.nodes.first(), Some(&(0_usize, 0_usize).into()));
+        assert_eq!(path.nodes.last(), Some(&cavern.southeast_corner()));
+
+        // there are several paths with a risk of 40, so check that this one is connected and
+        // adds up rather than comparing the exact route
+        for pair in path.nodes.windows(2) {
+            assert!(pair[0].cardinal_neighbours().contains(&pair[1]));
+        }
+        let risk: usize = path.nodes.iter().skip(1).map(|coord| cavern.get(*coord).0).sum();
+        assert_eq!(risk, 40);
+
+        let rendered = cavern.render(false, &path.nodes, Highlight::Dots);
+        assert_eq!(rendered.lines().count(), 10);
+        assert_eq!(rendered.chars().filter(|c| c.is_ascii_digit()).count(), path.nodes.len());
+    }
+
+    #[test]
+    fn algorithms_test() {
+        let input = "1163751742
+                     1381373672
+                     2136511328
+                     3694931569
+                     7463417111
+                     1319128137
+                     1359912421
+                     3125421639
+                     1293138521
+                     2311944581";
+        let cavern = parse_input(split_lines(input)).expect("could not parse input");
+
+        for algorithm in Algorithm::ALL {
+            let path = cavern
+                .lowest_risk_path_with(cavern.southeast_corner(), algorithm)
+                .expect("could not find a path");
+            assert_eq!(path.cost, 40, "{}", algorithm.name());
+            let risk: usize = path.nodes.iter().skip(1).map(|coord| cavern.get(*coord).0).sum();
+            assert_eq!(risk, 40, "{}", algorithm.name());
+
+            let path = cavern
+                .lowest_risk_path_with(cavern.tiled_southeast_corner(), algorithm)
+                .expect("could not find a path");
+            assert_eq!(path.cost, 315, "{}", algorithm.name());
+
+            assert_eq!(Algorithm::from_str(algorithm.name()).unwrap(), algorithm);
+        }
+
+        assert_eq!(
+            cavern.lowest_risk_path_with((50_usize, 50_usize).into(), Algorithm::Bucket),
+            None
+        );
+        assert!(Algorithm::from_str("bfs").is_err());
+    }
+
+    #[test]
+    fn tiling_test() {
+        let input = "1163751742
+                     1381373672
+                     2136511328
+                     3694931569
+                     7463417111
+                     1319128137
+                     1359912421
+                     3125421639
+                     1293138521
+                     2311944581";
+
+        let cavern = parse_input(split_lines(input))
+            .expect("could not parse input")
+            .with_tiles(1)
+            .unwrap();
+        assert!(parse_input(split_lines(input)).unwrap().with_tiles(0).is_err());
+
+        // a cavern without any columns is rejected before it can be searched
+        assert!(parse_input(vec!["".to_string()]).err().is_some_and(|e| e.is_parse()));
+        assert_eq!(cavern.tiled_southeast_corner(), cavern.southeast_corner());
+        assert_eq!(part_two(&cavern).unwrap(), 40);
+
+        let cavern = parse_input(split_lines(input))
+            .expect("could not parse input")
+            .with_tiles(10)
+            .unwrap();
+        assert_eq!(cavern.tiled_southeast_corner(), (99_usize, 99_usize).into());
+        assert_eq!(cavern.get((90_usize, 90_usize).into()), Risk(1));
+        assert_eq!(cavern.get((90_usize, 91_usize).into()), Risk(1));
+        assert_eq!(cavern.get((90_usize, 92_usize).into()), Risk(6));
+        assert!(cavern.get((99_usize, 99_usize).into()).value() > 0);
+
+        let risks: Vec<usize> = Algorithm::ALL
+            .iter()
+            .map(|algorithm| {
+                cavern
+                    .lowest_risk_path_with(cavern.tiled_southeast_corner(), *algorithm)
+                    .map(|path| path.cost)
+                    .expect("could not find a path")
+            })
+            .collect();
+        assert!(risks.windows(2).all(|pair| pair[0] == pair[1]));
+    }
+
+    #[test]
+    fn wrap_test() {
+        let input = "19
+                     91";
+
+        // without any change between tiles, every route alternates between risks of 9 and 1
+        let cavern = parse_input(split_lines(input))
+            .expect("could not parse input")
+            .with_wrap(|risk, _| risk);
+        assert_eq!(cavern.get((3_usize, 2_usize).into()), Risk(9));
+        assert_eq!(part_two(&cavern).unwrap(), 9 * 9 + 9);
+
+        // risk levels can go above 9, which the bucket queue has to make room for
+        let cavern = parse_input(split_lines(input))
+            .expect("could not parse input")
+            .with_wrap(|risk, distance| Risk(risk.value() + distance * 10));
+        assert_eq!(cavern.get((9_usize, 9_usize).into()), Risk(81));
+        for algorithm in Algorithm::ALL {
+            let expected = cavern.lowest_risk_path_with(cavern.tiled_southeast_corner(), Algorithm::Dijkstra);
+            let actual = cavern.lowest_risk_path_with(cavern.tiled_southeast_corner(), algorithm);
+            assert_eq!(actual.map(|path| path.cost), expected.map(|path| path.cost));
+        }
+    }
+
+    #[test]
+    fn render_test() {
+        let cavern = parse_input(vec!["8".to_string()]).expect("could not parse input");
+        let path: Vec<Coordinate> = vec![(0_usize, 0_usize).into(), (0_usize, 1_usize).into()];
+
+        let expected = "89...
+                        .....
+                        .....
+                        .....
+                        .....";
+        assert_eq!(
+            cavern.render(true, &path, Highlight::Dots),
+            split_lines(expected).join("\n")
+        );
+        assert_eq!(
+            cavern.render(false, &path, Highlight::Ansi),
+            "\x1b[1;31m8\x1b[0m"
+        );
     }
 }
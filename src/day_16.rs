@@ -1,225 +1,1669 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    io::{BufReader, Bytes, Read},
+    ops::Range,
+    str::FromStr,
+};
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::{bigint::BigUint, bits::BitCursor, error::AocError, solution::Solution};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = 16;
+    const TITLE: &'static str = "packet decoder";
+
+    type Input = Transmission;
+    type PartOne = usize;
+    type PartTwo = u64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(transmission: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(transmission)
+    }
+
+    fn part_two(transmission: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(transmission)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
 pub struct Transmission {
     bits: Vec<u64>,
     len: usize,
 }
 
-impl FromStr for Transmission {
-    type Err = ParseIntError;
+impl FromStr for Transmission {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let shorts = s
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c.to_digit(16) {
+                Some(digit) => Ok(digit as u8),
+                None => Err(AocError::parse("could not parse hex digit", c).at_column(i + 1)),
+            })
+            .collect::<Result<Vec<u8>, AocError>>()?;
+
+        let bits: Vec<u64> = shorts
+            .chunks(16)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rfold(0_u64, |a, e| a >> 4 | ((*e as u64) << 60))
+            })
+            .collect();
+
+        Ok(Self {
+            bits,
+            len: shorts.len() * 4,
+        })
+    }
+}
+
+impl From<&[u8]> for Transmission {
+    /// Reads raw bytes, most significant bit first
+    fn from(bytes: &[u8]) -> Self {
+        let bits = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0_u8; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_be_bytes(word)
+            })
+            .collect();
+
+        Self {
+            bits,
+            len: bytes.len() * 8,
+        }
+    }
+}
+
+impl Transmission {
+    /// Parses a string of `0` and `1` characters, one per bit
+    pub fn from_bits(s: &str) -> Result<Self, AocError> {
+        let mut transmission = Self::default();
+        for (i, c) in s.chars().enumerate() {
+            match c {
+                '0' => transmission.push(0, 1),
+                '1' => transmission.push(1, 1),
+                _ => return Err(AocError::parse("could not parse bit", c).at_column(i + 1)),
+            }
+        }
+
+        Ok(transmission)
+    }
+
+    /// Parses standard base64, with or without `=` padding
+    pub fn from_base64(s: &str) -> Result<Self, AocError> {
+        let trimmed = s.trim_end_matches('=');
+        if s.len() - trimmed.len() > 2 || trimmed.len() % 4 == 1 {
+            return Err(AocError::parse("invalid base64 length", s));
+        }
+
+        let mut transmission = Self::default();
+        for (i, c) in trimmed.chars().enumerate() {
+            let sextet = match c {
+                'A'..='Z' => c as u8 - b'A',
+                'a'..='z' => c as u8 - b'a' + 26,
+                '0'..='9' => c as u8 - b'0' + 52,
+                '+' => 62,
+                '/' => 63,
+                _ => return Err(AocError::parse("could not parse base64 digit", c).at_column(i + 1)),
+            };
+            transmission.push(sextet as u64, 6);
+        }
+
+        // the bits left over from the last partial byte are padding
+        transmission.len -= transmission.len % 8;
+        transmission.bits.truncate(transmission.len.div_ceil(64));
+        if let Some(last) = transmission.bits.last_mut() {
+            let used = transmission.len % 64;
+            if used > 0 {
+                *last &= !(u64::MAX >> used);
+            }
+        }
+
+        Ok(transmission)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a cursor at the start of the transmission
+    pub fn cursor(&self) -> BitCursor<&[u64]> {
+        BitCursor::new(&self.bits, self.len)
+    }
+
+    pub fn at(&self, index: usize) -> Option<u64> {
+        self.slice(index, 1)
+    }
+
+    /// Returns `len` bits starting at the given index, for lengths from 1 to 64
+    pub fn slice(&self, index: usize, len: usize) -> Option<u64> {
+        if len == 0 {
+            return None;
+        }
+
+        let mut cursor = self.cursor();
+        cursor.seek(index)?;
+        cursor.peek(len)
+    }
+
+    /// Renders the transmission as hex, padding the last digit with 0 bits if needed
+    pub fn to_hex(&self) -> String {
+        (0..self.len)
+            .step_by(4)
+            .map(|index| {
+                let len = (self.len - index).min(4);
+                let digit = self.slice(index, len).unwrap_or(0) << (4 - len);
+                std::char::from_digit(digit as u32, 16)
+                    .unwrap_or('0')
+                    .to_ascii_uppercase()
+            })
+            .collect()
+    }
+
+    /// Appends the lowest `width` bits of the value, most significant first
+    fn push(&mut self, value: u64, width: usize) {
+        for i in (0..width).rev() {
+            if self.len.is_multiple_of(64) {
+                self.bits.push(0);
+            }
+
+            let bit = (value >> i) & 1;
+            self.bits[self.len / 64] |= bit << (63 - self.len % 64);
+            self.len += 1;
+        }
+    }
+
+    /// Appends every bit of another transmission
+    fn append(&mut self, other: &Transmission) {
+        let mut cursor = other.cursor();
+        while !cursor.is_at_end() {
+            let width = cursor.remaining().min(64);
+            self.push(cursor.read(width).unwrap_or(0), width);
+        }
+    }
+
+    /// Decodes the outermost packet, which must be followed by nothing but 0 bits of padding
+    pub fn decode(&self, mode: Mode) -> Result<Decoded, DecodeError> {
+        self.decode_with(mode, &Operators::STANDARD)
+    }
+
+    /// Decodes the outermost packet, checking the arity of operators against the given table
+    pub fn decode_with(&self, mode: Mode, operators: &Operators) -> Result<Decoded, DecodeError> {
+        let mut cursor = self.cursor();
+        let mut reader = PacketReader {
+            cursor: &mut cursor,
+            operators,
+            mode,
+            path: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+
+        let packet = reader.packet()?;
+
+        // padding is checked a word at a time, then narrowed down to the first set bit
+        while !reader.cursor.is_at_end() {
+            let offset = reader.cursor.position();
+            let width = reader.cursor.remaining().min(64);
+            match reader.cursor.read(width) {
+                Some(0) | None => (),
+                Some(bits) => {
+                    let offset = offset + bits.leading_zeros() as usize - (64 - width);
+                    reader.report(DecodeError::new(DecodeErrorKind::Trailing, offset, Vec::new()))?;
+                    break;
+                }
+            }
+        }
+
+        Ok(Decoded {
+            packet,
+            diagnostics: reader.diagnostics,
+        })
+    }
+
+    /// Strictly decodes the packet at the start of the transmission, ignoring anything after it
+    pub fn parse_packet(&self) -> Result<Packet, DecodeError> {
+        Packet::read(&mut self.cursor())
+    }
+}
+
+/// How many subpackets an operator takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn allows(&self, count: usize) -> bool {
+        match self {
+            Self::Exactly(n) => count == *n,
+            Self::AtLeast(n) => count >= *n,
+        }
+    }
+
+    fn minimum(&self) -> usize {
+        match self {
+            Self::Exactly(n) | Self::AtLeast(n) => *n,
+        }
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (qualifier, n) = match self {
+            Self::Exactly(n) => ("exactly", n),
+            Self::AtLeast(n) => ("at least", n),
+        };
+        let plural = if *n == 1 { "" } else { "s" };
+        write!(f, "{} {} subpacket{}", qualifier, n, plural)
+    }
+}
+
+/// The semantics of an operator type id. The values of the subpackets are folded from the left
+/// with `apply`, so a single subpacket evaluates to its own value. When there is no value, such
+/// as on overflow, `apply` returns the reason, which is reported after the operator name
+#[derive(Debug, Clone, Copy)]
+pub struct Operator {
+    pub name: &'static str,
+    pub arity: Arity,
+    pub apply: fn(u64, u64) -> Result<u64, &'static str>,
+}
+
+/// The operator for each type id. Type ids are 3 bits and 4 is taken by literals, so an operator
+/// can be registered for any of the other 7, replacing the standard one
+#[derive(Debug, Clone, Copy)]
+pub struct Operators {
+    table: [Option<Operator>; 8],
+}
+
+impl Default for Operators {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl Operators {
+    /// The operators from the puzzle
+    pub const STANDARD: Self = Self {
+        table: [
+            Some(Operator {
+                name: "sum",
+                arity: Arity::AtLeast(1),
+                apply: |a, b| a.checked_add(b).ok_or("overflows 64 bits"),
+            }),
+            Some(Operator {
+                name: "product",
+                arity: Arity::AtLeast(1),
+                apply: |a, b| a.checked_mul(b).ok_or("overflows 64 bits"),
+            }),
+            Some(Operator {
+                name: "min",
+                arity: Arity::AtLeast(1),
+                apply: |a, b| Ok(a.min(b)),
+            }),
+            Some(Operator {
+                name: "max",
+                arity: Arity::AtLeast(1),
+                apply: |a, b| Ok(a.max(b)),
+            }),
+            None,
+            Some(Operator {
+                name: "gt",
+                arity: Arity::Exactly(2),
+                apply: |a, b| Ok((a > b) as u64),
+            }),
+            Some(Operator {
+                name: "lt",
+                arity: Arity::Exactly(2),
+                apply: |a, b| Ok((a < b) as u64),
+            }),
+            Some(Operator {
+                name: "eq",
+                arity: Arity::Exactly(2),
+                apply: |a, b| Ok((a == b) as u64),
+            }),
+        ],
+    };
+
+    pub fn get(&self, type_id: u8) -> Option<&Operator> {
+        self.table.get(type_id as usize).and_then(Option::as_ref)
+    }
+
+    /// Sets the operator for a type id, replacing any existing one
+    pub fn register(&mut self, type_id: u8, operator: Operator) -> Result<(), AocError> {
+        if type_id == Packet::LITERAL {
+            return Err(AocError::invalid("type id 4 is reserved for literals"));
+        }
+
+        if operator.arity.minimum() == 0 {
+            return Err(AocError::invalid(format!(
+                "{} must take at least 1 subpacket",
+                operator.name
+            )));
+        }
+
+        let slot = self.table.get_mut(type_id as usize).ok_or_else(|| {
+            AocError::invalid(format!("type id {} does not fit in 3 bits", type_id))
+        })?;
+        *slot = Some(operator);
+
+        Ok(())
+    }
+
+    /// Checks that an operator of the given type has a valid number of subpackets
+    fn check(&self, type_id: u8, found: usize) -> Option<DecodeErrorKind> {
+        let operator = self.get(type_id)?;
+        if operator.arity.allows(found) {
+            None
+        } else {
+            Some(DecodeErrorKind::Arity {
+                operator: operator.name,
+                expected: operator.arity,
+                found,
+            })
+        }
+    }
+}
+
+/// Whether malformed packets stop decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Any malformed packet is an error
+    Strict,
+
+    /// Wrong arities and bad padding are reported without stopping, and a subpacket of a bit
+    /// length operator that cannot be decoded is reported and skipped along with the rest of
+    /// its siblings
+    Lenient,
+}
+
+/// A decoded packet, along with any problems that were skipped in lenient mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub packet: Packet,
+    pub diagnostics: Vec<DecodeError>,
+}
+
+/// The field that was being read when a transmission ended early
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Version,
+    TypeId,
+    LengthTypeId,
+    BitLength,
+    PacketCount,
+    LiteralGroup,
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Version => "version",
+            Self::TypeId => "type id",
+            Self::LengthTypeId => "length type id",
+            Self::BitLength => "type 0 length",
+            Self::PacketCount => "type 1 length",
+            Self::LiteralGroup => "literal group",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// The transmission ended in the middle of a field
+    Truncated(Field),
+
+    /// The subpackets of a bit length operator ran past its end
+    Overrun { end: usize },
+
+    /// An operator has the wrong number of subpackets for its type
+    Arity {
+        operator: &'static str,
+        expected: Arity,
+        found: usize,
+    },
+
+    /// A literal needs more than 64 bits, which only the streaming decoder rejects
+    LiteralTooWide,
+
+    /// The outermost packet is followed by bits that are not 0 padding
+    Trailing,
+}
+
+impl Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated(field) => write!(f, "could not parse {}", field),
+            Self::Overrun { end } => {
+                write!(f, "subpackets overran their length, which ends at bit {}", end)
+            }
+            Self::Arity {
+                operator,
+                expected,
+                found,
+            } => write!(f, "{} expects {}, found {}", operator, expected, found),
+            Self::LiteralTooWide => write!(f, "literal does not fit in 64 bits"),
+            Self::Trailing => write!(f, "expected only 0 bits of padding after the outermost packet"),
+        }
+    }
+}
+
+/// A problem decoding a transmission, with the bit it was found at and the path of subpacket
+/// indices from the outermost packet to the packet at fault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub kind: DecodeErrorKind,
+    pub offset: usize,
+    pub path: Vec<usize>,
+}
+
+impl DecodeError {
+    pub fn new(kind: DecodeErrorKind, offset: usize, path: Vec<usize>) -> Self {
+        Self { kind, offset, path }
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at bit {}", self.kind, self.offset)?;
+        if !self.path.is_empty() {
+            let path = self
+                .path
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<String>>()
+                .join(".");
+            write!(f, " in subpacket {}", path)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for AocError {
+    fn from(e: DecodeError) -> Self {
+        AocError::parse(e.to_string(), "")
+    }
+}
+
+/// Decodes packets from a transmission, keeping track of the path to the current packet
+struct PacketReader<'a, W> {
+    cursor: &'a mut BitCursor<W>,
+    operators: &'a Operators,
+    mode: Mode,
+    path: Vec<usize>,
+    diagnostics: Vec<DecodeError>,
+}
+
+impl<W> PacketReader<'_, W>
+where
+    W: AsRef<[u64]>,
+{
+    fn packet(&mut self) -> Result<Packet, DecodeError> {
+        let start = self.cursor.position();
+        let version = self.read(3, Field::Version)? as u8;
+        let type_id = self.read(3, Field::TypeId)? as u8;
+
+        let payload = match type_id {
+            Packet::LITERAL => PacketPayload::Literal(self.literal()?),
+            _ => PacketPayload::Operator(self.operator(start, type_id)?),
+        };
+
+        Ok(Packet {
+            version,
+            type_id,
+            payload,
+            bits: Some(start..self.cursor.position()),
+        })
+    }
+
+    fn operator(&mut self, start: usize, type_id: u8) -> Result<OperatorPayload, DecodeError> {
+        let mut subpackets: Vec<Packet> = Vec::default();
+        let length_type = match self.read(1, Field::LengthTypeId)? {
+            0 => {
+                // length is total length in bits
+                let length = self.read(15, Field::BitLength)? as usize;
+                let end = self.cursor.position() + length;
+                while self.cursor.position() < end {
+                    self.path.push(subpackets.len());
+                    let subpacket = self.packet();
+                    self.path.pop();
+
+                    match subpacket {
+                        Ok(subpacket) => subpackets.push(subpacket),
+                        Err(e) if self.mode == Mode::Lenient => {
+                            self.diagnostics.push(e);
+                            let len = self.cursor.len();
+                            self.cursor.seek(end.min(len));
+                            break;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+
+                if self.cursor.position() > end {
+                    let kind = DecodeErrorKind::Overrun { end };
+                    self.report(DecodeError::new(kind, start, self.path.clone()))?;
+                }
+
+                LengthType::Bits
+            }
+            _ => {
+                // length is number of subpackets
+                let length = self.read(11, Field::PacketCount)? as usize;
+                for i in 0..length {
+                    self.path.push(i);
+                    let subpacket = self.packet();
+                    self.path.pop();
+                    subpackets.push(subpacket?);
+                }
+
+                LengthType::Packets
+            }
+        };
+
+        if let Some(kind) = self.operators.check(type_id, subpackets.len()) {
+            self.report(DecodeError::new(kind, start, self.path.clone()))?;
+        }
+
+        Ok(OperatorPayload {
+            length_type,
+            packets: subpackets,
+        })
+    }
+
+    fn literal(&mut self) -> Result<LiteralPayload, DecodeError> {
+        let mut number = 0_u64;
+        let mut big: Option<BigUint> = None;
+
+        loop {
+            let chunk = self.read(5, Field::LiteralGroup)?;
+            let nibble = chunk & 0b1111;
+            match &mut big {
+                Some(big) => big.mul_add(16, nibble as u32),
+                // switch to a big integer rather than shifting bits out of the top
+                None if number >> 60 != 0 => {
+                    let mut wide = BigUint::from(number);
+                    wide.mul_add(16, nibble as u32);
+                    big = Some(wide);
+                }
+                None => number = number << 4 | nibble,
+            }
+            if chunk >> 4 == 0 {
+                break;
+            }
+        }
+
+        Ok(LiteralPayload {
+            number: big.map(Number::from).unwrap_or(Number::Small(number)),
+        })
+    }
+
+    fn read(&mut self, width: usize, field: Field) -> Result<u64, DecodeError> {
+        let position = self.cursor.position();
+        self.cursor.read(width).ok_or_else(|| {
+            DecodeError::new(DecodeErrorKind::Truncated(field), position, self.path.clone())
+        })
+    }
+
+    /// Fails in strict mode, or records the problem and carries on in lenient mode
+    fn report(&mut self, e: DecodeError) -> Result<(), DecodeError> {
+        match self.mode {
+            Mode::Strict => Err(e),
+            Mode::Lenient => {
+                self.diagnostics.push(e);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A decoded packet. Packets read from a transmission remember the bits they were read from, which
+/// are ignored when comparing packets
+#[derive(Debug, Clone)]
+pub struct Packet {
+    version: u8,
+    type_id: u8,
+    payload: PacketPayload,
+    bits: Option<Range<usize>>,
+}
+
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version
+            && self.type_id == other.type_id
+            && self.payload == other.payload
+    }
+}
+
+impl Eq for Packet {}
+
+/// Renders the packet as an infix expression, such as `(3 + 4) * min(5, 9)`
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.infix_with(&Operators::STANDARD))
+    }
+}
+
+/// A packet rendered as an infix expression, naming operators as in a table. Only the standard
+/// arithmetic and comparison operators have symbols, others are written as calls
+pub struct Infix<'a> {
+    packet: &'a Packet,
+    operators: &'a Operators,
+}
+
+impl Display for Infix<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Infix { packet, operators } = *self;
+        let packets = match &packet.payload {
+            PacketPayload::Literal(LiteralPayload { number }) => return write!(f, "{}", number),
+            PacketPayload::Operator(OperatorPayload { packets, .. }) => packets,
+        };
+
+        let symbol = match packet.symbol(operators) {
+            Some(symbol) if !packets.is_empty() => symbol,
+            _ => {
+                write!(f, "{}(", packet.name_with(operators))?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", packet.infix_with(operators))?;
+                }
+                return write!(f, ")");
+            }
+        };
+
+        for (i, child) in packets.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", symbol)?;
+            }
+            if packets.len() > 1 && child.is_infix(operators) {
+                write!(f, "({})", child.infix_with(operators))?;
+            } else {
+                write!(f, "{}", child.infix_with(operators))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Packet {
+    /// The type id of literal value packets
+    pub const LITERAL: u8 = 4;
+
+    pub fn literal(version: u8, number: u64) -> Self {
+        Self::wide_literal(version, Number::Small(number))
+    }
+
+    /// Creates a literal packet whose number may need more than 64 bits
+    pub fn wide_literal(version: u8, number: Number) -> Self {
+        Self {
+            version,
+            type_id: Self::LITERAL,
+            payload: PacketPayload::Literal(LiteralPayload { number }),
+            bits: None,
+        }
+    }
+
+    pub fn operator(
+        version: u8,
+        type_id: u8,
+        length_type: LengthType,
+        packets: Vec<Packet>,
+    ) -> Self {
+        Self {
+            version,
+            type_id,
+            payload: PacketPayload::Operator(OperatorPayload {
+                length_type,
+                packets,
+            }),
+            bits: None,
+        }
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn type_id(&self) -> u8 {
+        self.type_id
+    }
+
+    /// The subpackets of an operator packet, which is empty for literals
+    pub fn children(&self) -> &[Packet] {
+        match &self.payload {
+            PacketPayload::Operator(OperatorPayload { packets, .. }) => packets,
+            PacketPayload::Literal(_) => &[],
+        }
+    }
+
+    /// The number held by a literal packet, if it fits in 64 bits
+    pub fn value(&self) -> Option<u64> {
+        self.number().and_then(Number::to_u64)
+    }
+
+    /// The number held by a literal packet
+    pub fn number(&self) -> Option<&Number> {
+        match &self.payload {
+            PacketPayload::Literal(LiteralPayload { number }) => Some(number),
+            PacketPayload::Operator(_) => None,
+        }
+    }
+
+    pub fn length_type(&self) -> Option<LengthType> {
+        match &self.payload {
+            PacketPayload::Operator(OperatorPayload { length_type, .. }) => Some(*length_type),
+            PacketPayload::Literal(_) => None,
+        }
+    }
+
+    /// The range of bits the packet was read from, if it was decoded from a transmission
+    pub fn bits(&self) -> Option<Range<usize>> {
+        self.bits.clone()
+    }
+
+    /// The name of the operation given by the type id
+    pub fn name(&self) -> &'static str {
+        self.name_with(&Operators::STANDARD)
+    }
+
+    /// The name of the operation given by the type id in an operator table
+    pub fn name_with(&self, operators: &Operators) -> &'static str {
+        match operators.get(self.type_id) {
+            Some(operator) => operator.name,
+            None if self.type_id == Self::LITERAL => "literal",
+            None => "unknown",
+        }
+    }
+
+    /// Renders the packet tree as an indented outline, one packet per line, such as
+    ///
+    /// ```text
+    /// sum (version 0, type 0, bits 0..49)
+    ///   literal 1 (version 6, type 4, bits 22..33)
+    /// ```
+    pub fn outline(&self) -> String {
+        self.outline_with(&Operators::STANDARD)
+    }
+
+    /// Renders the packet tree as an outline, naming operators as in a table
+    pub fn outline_with(&self, operators: &Operators) -> String {
+        let mut lines = Vec::new();
+        self.outline_into(operators, 0, &mut lines);
+        lines.join("\n")
+    }
+
+    fn outline_into(&self, operators: &Operators, depth: usize, lines: &mut Vec<String>) {
+        let mut line = format!("{}{}", "  ".repeat(depth), self.name_with(operators));
+        if let Some(number) = self.number() {
+            line += &format!(" {}", number);
+        }
+        line += &format!(" (version {}, type {}", self.version, self.type_id);
+        if let Some(bits) = &self.bits {
+            line += &format!(", bits {}..{}", bits.start, bits.end);
+        }
+        line += ")";
+        lines.push(line);
+
+        for packet in self.children() {
+            packet.outline_into(operators, depth + 1, lines);
+        }
+    }
+
+    /// Renders the packet as an S-expression, such as `(* (+ 3 4) (min 5 9))`
+    pub fn sexpr(&self) -> String {
+        self.sexpr_with(&Operators::STANDARD)
+    }
+
+    /// Renders the packet as an S-expression, naming operators as in a table
+    pub fn sexpr_with(&self, operators: &Operators) -> String {
+        if let Some(number) = self.number() {
+            return number.to_string();
+        }
+
+        let head = match self.symbol(operators) {
+            Some("==") => "=",
+            Some(symbol) => symbol,
+            None => self.name_with(operators),
+        };
+        let terms = self
+            .children()
+            .iter()
+            .map(|packet| format!(" {}", packet.sexpr_with(operators)))
+            .collect::<String>();
+
+        format!("({}{})", head, terms)
+    }
+
+    /// Renders the packet as an infix expression, naming operators as in a table
+    pub fn infix_with<'a>(&'a self, operators: &'a Operators) -> Infix<'a> {
+        Infix {
+            packet: self,
+            operators,
+        }
+    }
+
+    /// The infix symbol for the operator, if it is one of the standard operators that has one.
+    /// Operators registered in their place are written by name instead
+    fn symbol(&self, operators: &Operators) -> Option<&'static str> {
+        let operator = operators.get(self.type_id)?;
+        let standard = Operators::STANDARD.get(self.type_id)?;
+        if operator.name != standard.name {
+            return None;
+        }
+
+        match self.type_id {
+            0 => Some("+"),
+            1 => Some("*"),
+            5 => Some(">"),
+            6 => Some("<"),
+            7 => Some("=="),
+            _ => None,
+        }
+    }
+
+    /// Whether the packet renders as a bare infix expression, which needs brackets when nested.
+    /// An infix operator with a single child renders as just that child
+    fn is_infix(&self, operators: &Operators) -> bool {
+        if self.symbol(operators).is_none() {
+            return false;
+        }
+
+        match self.children() {
+            [only] => only.is_infix(operators),
+            children => children.len() > 1,
+        }
+    }
+
+    /// Strictly decodes the packet at the cursor, leaving the cursor just after it
+    pub fn read<W: AsRef<[u64]>>(cursor: &mut BitCursor<W>) -> Result<Packet, DecodeError> {
+        PacketReader {
+            cursor,
+            operators: &Operators::STANDARD,
+            mode: Mode::Strict,
+            path: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+        .packet()
+    }
+
+    /// Serialises the packet into a transmission, which `parse_packet` decodes back into an
+    /// equal packet
+    pub fn encode(&self) -> Result<Transmission, AocError> {
+        let mut transmission = Transmission::default();
+        self.write(&mut transmission)?;
+        Ok(transmission)
+    }
+
+    fn write(&self, out: &mut Transmission) -> Result<(), AocError> {
+        if self.version > 7 {
+            return Err(AocError::invalid(format!(
+                "version {} does not fit in 3 bits",
+                self.version
+            )));
+        }
+
+        if self.type_id > 7 {
+            return Err(AocError::invalid(format!(
+                "type id {} does not fit in 3 bits",
+                self.type_id
+            )));
+        }
+
+        out.push(self.version as u64, 3);
+        out.push(self.type_id as u64, 3);
+
+        match &self.payload {
+            PacketPayload::Literal(LiteralPayload { number }) => {
+                // split the number into groups of 4 bits, each prefixed by whether more follow
+                let groups = number.bits().div_ceil(4);
+                for i in (0..groups.max(1)).rev() {
+                    out.push((i > 0) as u64, 1);
+                    out.push(number.nibble(i) as u64, 4);
+                }
+            }
+            PacketPayload::Operator(OperatorPayload {
+                length_type,
+                packets,
+            }) => {
+                if self.type_id == Self::LITERAL {
+                    return Err(AocError::invalid(
+                        "operator packets cannot have the literal type id",
+                    ));
+                }
+
+                let mut subpackets = Transmission::default();
+                for packet in packets {
+                    packet.write(&mut subpackets)?;
+                }
+
+                match length_type {
+                    LengthType::Bits => {
+                        if subpackets.len() >= 1 << 15 {
+                            return Err(AocError::invalid(format!(
+                                "{} bits of subpackets do not fit in a 15 bit length",
+                                subpackets.len()
+                            )));
+                        }
+                        out.push(0, 1);
+                        out.push(subpackets.len() as u64, 15);
+                    }
+                    LengthType::Packets => {
+                        if packets.len() >= 1 << 11 {
+                            return Err(AocError::invalid(format!(
+                                "{} subpackets do not fit in an 11 bit count",
+                                packets.len()
+                            )));
+                        }
+                        out.push(1, 1);
+                        out.push(packets.len() as u64, 11);
+                    }
+                }
+
+                out.append(&subpackets);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn version_sum(&self) -> usize {
+        match &self.payload {
+            PacketPayload::Operator(OperatorPayload { packets, .. }) => {
+                packets.iter().map(|x| x.version_sum()).sum::<usize>() + (self.version as usize)
+            }
+            PacketPayload::Literal(_) => self.version as usize,
+        }
+    }
+
+    /// Evaluates the expression with 64 bit arithmetic, failing if any literal, sum or product
+    /// does not fit
+    pub fn eval(&self) -> Result<u64, AocError> {
+        self.eval_with(&Operators::STANDARD)
+    }
+
+    /// Evaluates the expression with the semantics given by an operator table
+    pub fn eval_with(&self, operators: &Operators) -> Result<u64, AocError> {
+        let packets = match &self.payload {
+            PacketPayload::Literal(LiteralPayload { number }) => {
+                return number
+                    .to_u64()
+                    .ok_or_else(|| self.no_value("literal", "overflows 64 bits"));
+            }
+            PacketPayload::Operator(OperatorPayload { packets, .. }) => packets,
+        };
+
+        let operator = operators
+            .get(self.type_id)
+            .ok_or_else(|| AocError::no_solution("invalid operator type id"))?;
+        if let Some(kind) = operators.check(self.type_id, packets.len()) {
+            return Err(AocError::no_solution(kind.to_string()));
+        }
+
+        let mut value: Option<u64> = None;
+        for packet in packets {
+            let term = packet.eval_with(operators)?;
+            value = match value {
+                Some(value) => Some(
+                    (operator.apply)(value, term).map_err(|reason| self.no_value(operator.name, reason))?,
+                ),
+                None => Some(term),
+            };
+        }
+
+        value.ok_or_else(|| AocError::no_solution("not enough terms"))
+    }
+
+    /// Evaluates the expression exactly with the standard operators, falling back to big
+    /// integers where 64 bits overflow
+    pub fn eval_exact(&self) -> Result<Number, AocError> {
+        let packets = match &self.payload {
+            PacketPayload::Literal(LiteralPayload { number }) => return Ok(number.clone()),
+            PacketPayload::Operator(OperatorPayload { packets, .. }) => packets,
+        };
+
+        let terms = packets
+            .iter()
+            .map(|x| x.eval_exact())
+            .collect::<Result<Vec<Number>, AocError>>()?;
+        match (&self.type_id, terms.as_slice()) {
+            (0, _) => Ok(terms.iter().fold(Number::Small(0), |a, e| a.add(e))),
+            (1, _) => Ok(terms.iter().fold(Number::Small(1), |a, e| a.mul(e))),
+            (2, _) => terms
+                .into_iter()
+                .min()
+                .ok_or_else(|| AocError::no_solution("not enough terms for min")),
+            (3, _) => terms
+                .into_iter()
+                .max()
+                .ok_or_else(|| AocError::no_solution("not enough terms for max")),
+            (5, [a, b]) => Ok(Number::Small((a > b) as u64)),
+            (6, [a, b]) => Ok(Number::Small((a < b) as u64)),
+            (7, [a, b]) => Ok(Number::Small((a == b) as u64)),
+            (5..=7, _) => Err(AocError::no_solution(
+                "expected comparison to have exactly 2 terms",
+            )),
+            _ => Err(AocError::no_solution("invalid operator type id")),
+        }
+    }
+
+    fn no_value(&self, name: &str, reason: &str) -> AocError {
+        let mut reason = format!("{} {}", name, reason);
+        if let Some(bits) = &self.bits {
+            reason += &format!(" at bit {}", bits.start);
+        }
+        AocError::no_solution(reason)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketPayload {
+    Operator(OperatorPayload),
+    Literal(LiteralPayload),
+}
+
+/// How an operator packet says where its subpackets end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    /// Length type id 0, a 15 bit total length of the subpackets in bits
+    Bits,
+
+    /// Length type id 1, an 11 bit number of subpackets
+    Packets,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorPayload {
+    length_type: LengthType,
+    packets: Vec<Packet>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiteralPayload {
+    number: Number,
+}
+
+/// A literal or evaluated number, which only uses a big integer when it does not fit in a `u64`
+#[derive(Debug, Clone)]
+pub enum Number {
+    Small(u64),
+    Big(BigUint),
+}
+
+impl From<u64> for Number {
+    fn from(number: u64) -> Self {
+        Self::Small(number)
+    }
+}
+
+impl From<BigUint> for Number {
+    fn from(number: BigUint) -> Self {
+        match number.to_u64() {
+            Some(small) => Self::Small(small),
+            None => Self::Big(number),
+        }
+    }
+}
+
+impl Number {
+    pub fn to_u64(&self) -> Option<u64> {
+        match self {
+            Self::Small(number) => Some(*number),
+            Self::Big(number) => number.to_u64(),
+        }
+    }
+
+    pub fn to_big(&self) -> BigUint {
+        match self {
+            Self::Small(number) => BigUint::from(*number),
+            Self::Big(number) => number.clone(),
+        }
+    }
+
+    /// The number of significant bits, which is 0 for zero
+    pub fn bits(&self) -> usize {
+        match self {
+            Self::Small(number) => 64 - number.leading_zeros() as usize,
+            Self::Big(number) => number.bits(),
+        }
+    }
+
+    /// Returns the `i`th group of 4 bits, counting from the least significant
+    pub fn nibble(&self, i: usize) -> u8 {
+        match self {
+            Self::Small(number) => number.checked_shr(i as u32 * 4).unwrap_or(0) as u8 & 0b1111,
+            Self::Big(number) => number.nibble(i),
+        }
+    }
+
+    pub fn add(&self, other: &Number) -> Number {
+        match (self, other) {
+            (Self::Small(a), Self::Small(b)) if a.checked_add(*b).is_some() => Self::Small(a + b),
+            _ => Self::from(&self.to_big() + &other.to_big()),
+        }
+    }
+
+    pub fn mul(&self, other: &Number) -> Number {
+        match (self, other) {
+            (Self::Small(a), Self::Small(b)) if a.checked_mul(*b).is_some() => Self::Small(a * b),
+            _ => Self::from(&self.to_big() * &other.to_big()),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let shorts = s
-            .chars()
-            .map(|c| u8::from_str_radix(&c.to_string(), 16))
-            .collect::<Result<Vec<u8>, ParseIntError>>()?;
+impl Eq for Number {}
 
-        let bits: Vec<u64> = shorts
-            .chunks(16)
-            .map(|chunk| {
-                chunk
-                    .iter()
-                    .rfold(0_u64, |a, e| a >> 4 | ((*e as u64) << 60))
-            })
-            .collect();
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Small(a), Self::Small(b)) => a.cmp(b),
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
 
-        Ok(Self {
-            bits,
-            len: shorts.len() * 4,
-        })
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl Transmission {
-    pub fn len(&self) -> usize {
-        self.len
+impl Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Small(number) => write!(f, "{}", number),
+            Self::Big(number) => write!(f, "{}", number),
+        }
     }
+}
 
-    pub fn at(&self, index: usize) -> Option<u16> {
-        self.slice(index, 1)
+/// How the bytes of a streamed transmission encode its bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// One hex digit per byte, as in the puzzle input. Whitespace is skipped
+    Hex,
+
+    /// Eight bits per byte, most significant first
+    Raw,
+}
+
+/// Reads a transmission bit by bit from any `Read`. Input is buffered in chunks of 8 KiB, so
+/// memory use stays the same however long the transmission is
+struct BitReader<R: Read> {
+    bytes: Bytes<BufReader<R>>,
+    encoding: Encoding,
+    current: u8,
+    available: u8,
+    position: usize,
+    column: usize,
+}
+
+impl<R: Read> BitReader<R> {
+    fn new(reader: R, encoding: Encoding) -> Self {
+        Self {
+            bytes: BufReader::new(reader).bytes(),
+            encoding,
+            current: 0,
+            available: 0,
+            position: 0,
+            column: 0,
+        }
     }
 
-    pub fn slice(&self, index: usize, len: usize) -> Option<u16> {
-        let end = index + len; // exclusive
+    /// Reads up to 64 bits, most significant first, or returns `None` if the stream ends first
+    fn read(&mut self, width: usize) -> Result<Option<u64>, AocError> {
+        let mut value = 0_u64;
+        for _ in 0..width {
+            if self.available == 0 && !self.fill()? {
+                return Ok(None);
+            }
+
+            self.available -= 1;
+            value = value << 1 | ((self.current >> self.available) & 1) as u64;
+            self.position += 1;
+        }
 
-        if end > self.len() || len == 0 || len > 16 {
-            return None;
+        Ok(Some(value))
+    }
+
+    /// Loads the next byte of bits, returning false at the end of the stream
+    fn fill(&mut self) -> Result<bool, AocError> {
+        while let Some(byte) = self.bytes.next() {
+            let byte = byte?;
+            self.column += 1;
+            match self.encoding {
+                Encoding::Raw => {
+                    self.current = byte;
+                    self.available = 8;
+                    return Ok(true);
+                }
+                Encoding::Hex if byte.is_ascii_whitespace() => continue,
+                Encoding::Hex => {
+                    let c = byte as char;
+                    let digit = c.to_digit(16).ok_or_else(|| {
+                        AocError::parse("could not parse hex digit", c).at_column(self.column)
+                    })?;
+                    self.current = digit as u8;
+                    self.available = 4;
+                    return Ok(true);
+                }
+            }
         }
 
-        let start_i = index / 64;
-        let start_j = index % 64;
-        let start_chunk = self.bits[start_i];
+        Ok(false)
+    }
+}
+
+/// An event emitted while decoding a transmission. Every `Enter` is matched by an `Exit` once all
+/// of the operator's subpackets have been emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Enter {
+        version: u8,
+        type_id: u8,
+        length_type: LengthType,
+        offset: usize,
+    },
+    Literal {
+        version: u8,
+        number: u64,
+        offset: usize,
+    },
+    Exit {
+        type_id: u8,
+    },
+}
+
+/// Where an open operator packet ends
+#[derive(Debug, Clone, Copy)]
+enum Remaining {
+    /// The bit position just after the last subpacket
+    Until(usize),
+
+    /// The number of subpackets still to be read
+    Packets(usize),
+}
+
+/// An operator packet whose subpackets are still being decoded
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    type_id: u8,
+    offset: usize,
+    remaining: Remaining,
+    children: usize,
+}
+
+/// Decodes the outermost packet of a transmission as a stream of events, without building the
+/// packet tree. Memory use grows with the nesting depth of the packets, not the size of the
+/// transmission
+pub struct Decoder<R: Read> {
+    bits: BitReader<R>,
+    operators: Operators,
+    open: Vec<Frame>,
+    started: bool,
+    done: bool,
+}
 
-        if start_j + len <= 64 {
-            return Some((start_chunk << start_j >> (64 - len)) as u16);
+impl<R: Read> Decoder<R> {
+    pub fn new(reader: R, encoding: Encoding) -> Self {
+        Self {
+            bits: BitReader::new(reader, encoding),
+            operators: Operators::STANDARD,
+            open: Vec::new(),
+            started: false,
+            done: false,
         }
+    }
 
-        // end index is exclusive
-        let end_i = end / 64;
-        let end_j = end % 64;
-        let end_chunk = self.bits[end_i];
+    pub fn hex(reader: R) -> Self {
+        Self::new(reader, Encoding::Hex)
+    }
 
-        Some(((start_chunk << start_j >> start_j << end_j) | (end_chunk >> (64 - end_j))) as u16)
+    pub fn raw(reader: R) -> Self {
+        Self::new(reader, Encoding::Raw)
     }
 
-    pub fn parse_packet(&self, index: &mut usize) -> Result<Packet, String> {
-        let version = self
-            .slice(*index, 3)
-            .ok_or_else(|| "could not parse version")? as u8;
-        *index += 3;
+    /// Checks the arity of operators against the given table instead of the standard one
+    pub fn with_operators(mut self, operators: Operators) -> Self {
+        self.operators = operators;
+        self
+    }
 
-        let type_id = self
-            .slice(*index, 3)
-            .ok_or_else(|| "could not parse type id")? as u8;
-        *index += 3;
+    /// The number of bits read so far
+    pub fn position(&self) -> usize {
+        self.bits.position
+    }
 
-        let payload = match type_id {
-            4 => PacketPayload::Literal(self.parse_literal(index)),
-            _ => PacketPayload::Operator(self.parse_operator(index)?),
-        };
+    /// Feeds every event to the visitor, stopping at the first error
+    pub fn accept(self, visitor: &mut impl Visitor) -> Result<(), AocError> {
+        for event in self {
+            visitor.visit(event?)?;
+        }
 
-        Ok(Packet {
-            version,
-            type_id,
-            payload,
-        })
+        Ok(())
     }
 
-    fn parse_operator(&self, index: &mut usize) -> Result<OperatorPayload, String> {
-        let length_type_id = self.at(*index);
-        *index += 1;
+    fn next_event(&mut self) -> Result<Option<Event>, AocError> {
+        if let Some(&frame) = self.open.last() {
+            let finished = match frame.remaining {
+                Remaining::Until(end) if self.bits.position > end => {
+                    let kind = DecodeErrorKind::Overrun { end };
+                    return Err(self.error(kind, frame.offset, self.open.len() - 1).into());
+                }
+                Remaining::Until(end) => self.bits.position == end,
+                Remaining::Packets(count) => count == 0,
+            };
 
-        let mut subpackets: Vec<Packet> = Vec::default();
-        match length_type_id {
-            Some(0) => {
-                // length is total length in bits
-                let length = self
-                    .slice(*index, 15)
-                    .ok_or_else(|| "could not parse type 0 length")?
-                    as usize;
-                *index += 15;
-
-                let end = *index + length;
-                while *index < end {
-                    let subpacket = self.parse_packet(index)?;
-                    subpackets.push(subpacket);
+            if finished {
+                if let Some(kind) = self.operators.check(frame.type_id, frame.children) {
+                    return Err(self.error(kind, frame.offset, self.open.len() - 1).into());
                 }
+
+                self.open.pop();
+                return Ok(Some(Event::Exit {
+                    type_id: frame.type_id,
+                }));
             }
-            Some(1) => {
-                // length is number of subpackets
-                let length = self
-                    .slice(*index, 11)
-                    .ok_or_else(|| "could not parse type 1 length")?
-                    as usize;
-                *index += 11;
-
-                for _ in 0..length {
-                    let subpacket = self.parse_packet(index)?;
-                    subpackets.push(subpacket);
+        } else if self.started {
+            self.check_padding()?;
+            self.done = true;
+            return Ok(None);
+        }
+
+        self.started = true;
+        if let Some(frame) = self.open.last_mut() {
+            frame.children += 1;
+            if let Remaining::Packets(count) = &mut frame.remaining {
+                *count -= 1;
+            }
+        }
+
+        let offset = self.bits.position;
+        let version = self.read(3, Field::Version)? as u8;
+        let type_id = self.read(3, Field::TypeId)? as u8;
+
+        if type_id == Packet::LITERAL {
+            let mut number = 0_u64;
+            loop {
+                let position = self.bits.position;
+                let chunk = self.read(5, Field::LiteralGroup)?;
+                if number >> 60 != 0 {
+                    let kind = DecodeErrorKind::LiteralTooWide;
+                    return Err(self.error(kind, position, self.open.len()).into());
+                }
+                number = number << 4 | (chunk & 0b1111);
+                if chunk >> 4 == 0 {
+                    break;
                 }
             }
-            _ => return Err("invalid length type id".to_string()),
+
+            return Ok(Some(Event::Literal {
+                version,
+                number,
+                offset,
+            }));
+        }
+
+        let (length_type, remaining) = match self.read(1, Field::LengthTypeId)? {
+            0 => {
+                let length = self.read(15, Field::BitLength)? as usize;
+                (LengthType::Bits, Remaining::Until(self.bits.position + length))
+            }
+            _ => {
+                let length = self.read(11, Field::PacketCount)? as usize;
+                (LengthType::Packets, Remaining::Packets(length))
+            }
         };
+        self.open.push(Frame {
+            type_id,
+            offset,
+            remaining,
+            children: 0,
+        });
 
-        Ok(OperatorPayload {
-            packets: subpackets,
-        })
+        Ok(Some(Event::Enter {
+            version,
+            type_id,
+            length_type,
+            offset,
+        }))
     }
 
-    fn parse_literal(&self, index: &mut usize) -> LiteralPayload {
-        let mut number = 0_u64;
+    /// Reads the rest of the stream, which must only be 0 bits
+    fn check_padding(&mut self) -> Result<(), AocError> {
+        loop {
+            let position = self.bits.position;
+            match self.bits.read(1)? {
+                Some(0) => continue,
+                Some(_) => {
+                    return Err(DecodeError::new(DecodeErrorKind::Trailing, position, Vec::new()).into())
+                }
+                None => return Ok(()),
+            }
+        }
+    }
 
-        while let Some(chunk) = self.slice(*index, 5) {
-            *index += 5;
-            number = number << 4 | ((chunk & 0b1111) as u64);
-            if chunk >> 4 == 0 {
-                break;
+    fn read(&mut self, width: usize, field: Field) -> Result<u64, AocError> {
+        let position = self.bits.position;
+        match self.bits.read(width)? {
+            Some(value) => Ok(value),
+            None => {
+                let kind = DecodeErrorKind::Truncated(field);
+                Err(self.error(kind, position, self.open.len()).into())
             }
         }
+    }
 
-        LiteralPayload { number }
+    /// Creates an error for the packet whose path runs through the given number of open operators
+    fn error(&self, kind: DecodeErrorKind, offset: usize, depth: usize) -> DecodeError {
+        let path = self.open[..depth]
+            .iter()
+            .map(|frame| frame.children.saturating_sub(1))
+            .collect();
+        DecodeError::new(kind, offset, path)
     }
 }
 
-pub struct Packet {
-    version: u8,
-    type_id: u8,
-    payload: PacketPayload,
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = Result<Event, AocError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let event = self.next_event();
+        if event.is_err() {
+            self.done = true;
+        }
+
+        event.transpose()
+    }
 }
 
-impl Packet {
-    pub fn version_sum(&self) -> usize {
-        match &self.payload {
-            PacketPayload::Operator(OperatorPayload { packets }) => {
-                packets.iter().map(|x| x.version_sum()).sum::<usize>() + (self.version as usize)
+/// Receives the events of a `Decoder`
+pub trait Visitor {
+    fn visit(&mut self, event: Event) -> Result<(), AocError>;
+}
+
+/// Visits every event with both visitors, so that they can share a single pass
+impl<A: Visitor, B: Visitor> Visitor for (A, B) {
+    fn visit(&mut self, event: Event) -> Result<(), AocError> {
+        self.0.visit(event)?;
+        self.1.visit(event)
+    }
+}
+
+/// Sums the versions of every packet
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VersionSum(pub usize);
+
+impl Visitor for VersionSum {
+    fn visit(&mut self, event: Event) -> Result<(), AocError> {
+        match event {
+            Event::Enter { version, .. } | Event::Literal { version, .. } => {
+                self.0 += version as usize
             }
-            PacketPayload::Literal(_) => self.version as usize,
+            Event::Exit { .. } => (),
         }
+
+        Ok(())
     }
+}
 
-    pub fn eval(&self) -> Result<u64, String> {
-        match &self.payload {
-            PacketPayload::Literal(LiteralPayload { number }) => Ok(*number),
-            PacketPayload::Operator(OperatorPayload { packets }) => {
-                let terms = packets
-                    .iter()
-                    .map(|x| x.eval())
-                    .collect::<Result<Vec<u64>, String>>()?;
-                match &self.type_id {
-                    0 => Ok(terms.iter().sum()),
-                    1 => Ok(terms.iter().product()),
-                    2 => match terms.iter().min() {
-                        Some(min) => Ok(*min),
-                        None => Err("not enough terms for min".to_string()),
-                    },
-                    3 => match terms.iter().max() {
-                        Some(max) => Ok(*max),
-                        None => Err("not enough terms for max".to_string()),
-                    },
-                    5 => Ok((terms[0] > terms[1]) as u64),
-                    6 => Ok((terms[0] < terms[1]) as u64),
-                    7 => Ok((terms[0] == terms[1]) as u64),
-                    _ => Err("invalid operator type id".to_string()),
-                }
+/// Evaluates the expression, folding the terms of each open operator as they arrive
+#[derive(Debug, Default, Clone)]
+pub struct Evaluator {
+    operators: Operators,
+    open: Vec<Fold>,
+    value: Option<u64>,
+}
+
+impl Evaluator {
+    /// Evaluates with the semantics given by an operator table
+    pub fn new(operators: Operators) -> Self {
+        Self {
+            operators,
+            open: Vec::new(),
+            value: None,
+        }
+    }
+
+    /// The value of the outermost packet, once it has been exited
+    pub fn value(&self) -> Option<u64> {
+        self.value
+    }
+
+    fn push(&mut self, term: u64) -> Result<(), AocError> {
+        match self.open.last_mut() {
+            Some(fold) => fold.push(term),
+            None => {
+                self.value = Some(term);
+                Ok(())
             }
         }
     }
 }
 
-pub enum PacketPayload {
-    Operator(OperatorPayload),
-    Literal(LiteralPayload),
+impl Visitor for Evaluator {
+    fn visit(&mut self, event: Event) -> Result<(), AocError> {
+        match event {
+            Event::Enter { type_id, .. } => {
+                let operator = self
+                    .operators
+                    .get(type_id)
+                    .ok_or_else(|| AocError::no_solution("invalid operator type id"))?;
+                self.open.push(Fold {
+                    operator: *operator,
+                    terms: 0,
+                    value: None,
+                });
+                Ok(())
+            }
+            Event::Literal { number, .. } => self.push(number),
+            Event::Exit { .. } => {
+                let fold = self
+                    .open
+                    .pop()
+                    .ok_or_else(|| AocError::no_solution("exit without a matching enter"))?;
+                self.push(fold.finish()?)
+            }
+        }
+    }
 }
 
-pub struct OperatorPayload {
-    packets: Vec<Packet>,
+/// The running result of an operator whose terms are still being evaluated
+#[derive(Debug, Clone)]
+struct Fold {
+    operator: Operator,
+    terms: usize,
+    value: Option<u64>,
 }
 
-pub struct LiteralPayload {
-    number: u64,
+impl Fold {
+    fn push(&mut self, term: u64) -> Result<(), AocError> {
+        self.value = match self.value {
+            Some(value) => Some((self.operator.apply)(value, term).map_err(|reason| {
+                AocError::no_solution(format!("{} {}", self.operator.name, reason))
+            })?),
+            None => Some(term),
+        };
+        self.terms += 1;
+
+        Ok(())
+    }
+
+    fn finish(&self) -> Result<u64, AocError> {
+        let operator = &self.operator;
+        match self.value {
+            Some(value) if operator.arity.allows(self.terms) => Ok(value),
+            _ => Err(AocError::no_solution(format!(
+                "{} expects {}, found {}",
+                operator.name, operator.arity, self.terms
+            ))),
+        }
+    }
+}
+
+/// Computes the version sum and value of a transmission in a single streaming pass
+pub fn stream_summary(decoder: Decoder<impl Read>) -> Result<(usize, u64), AocError> {
+    let mut visitors = (VersionSum::default(), Evaluator::default());
+    decoder.accept(&mut visitors)?;
+
+    let (VersionSum(sum), evaluator) = visitors;
+    let value = evaluator
+        .value()
+        .ok_or_else(|| AocError::no_solution("transmission ended inside a packet"))?;
+
+    Ok((sum, value))
 }
 
-pub fn parse_input(lines: Vec<String>) -> Result<Transmission, String> {
+pub fn parse_input(lines: Vec<String>) -> Result<Transmission, AocError> {
     if lines.len() != 1 {
-        return Err("invalid input".to_string());
+        return Err(AocError::parse(
+            format!("expected exactly 1 line, found {}", lines.len()),
+            "",
+        ));
     }
 
-    Transmission::from_str(&lines[0]).or_else(|_| Err("could not parse input".to_string()))
+    Transmission::from_str(&lines[0]).map_err(|e| e.at_line(1))
 }
 
-pub fn part_one(transmission: &Transmission) -> usize {
-    let packet = transmission
-        .parse_packet(&mut 0)
-        .expect("could not parse packet");
-    packet.version_sum()
+pub fn part_one(transmission: &Transmission) -> Result<usize, AocError> {
+    let packet = transmission.decode(Mode::Strict)?.packet;
+    Ok(packet.version_sum())
 }
 
-pub fn part_two(transmission: &Transmission) -> u64 {
-    let packet = transmission
-        .parse_packet(&mut 0)
-        .expect("could not parse packet");
-    packet.eval().expect("could not eval the transmission")
+pub fn part_two(transmission: &Transmission) -> Result<u64, AocError> {
+    let packet = transmission.decode(Mode::Strict)?.packet;
+    packet.eval()
 }
 
 #[cfg(test)]
@@ -258,59 +1702,59 @@ mod tests {
     #[test]
     fn part_one_test() {
         let mut trans = parse_input(vec!["D2FE28".to_string()]).expect("could not parse input");
-        assert_eq!(part_one(&trans), 6);
+        assert_eq!(part_one(&trans).unwrap(), 6);
 
         trans = parse_input(vec!["38006F45291200".to_string()]).expect("could not parse input");
-        assert_eq!(part_one(&trans), 9);
+        assert_eq!(part_one(&trans).unwrap(), 9);
 
         trans = parse_input(vec!["EE00D40C823060".to_string()]).expect("could not parse input");
-        assert_eq!(part_one(&trans), 14);
+        assert_eq!(part_one(&trans).unwrap(), 14);
 
         trans = parse_input(vec!["8A004A801A8002F478".to_string()]).expect("could not parse input");
-        assert_eq!(part_one(&trans), 16);
+        assert_eq!(part_one(&trans).unwrap(), 16);
 
         trans = parse_input(vec!["620080001611562C8802118E34".to_string()])
             .expect("could not parse input");
-        assert_eq!(part_one(&trans), 12);
+        assert_eq!(part_one(&trans).unwrap(), 12);
 
         trans = parse_input(vec!["C0015000016115A2E0802F182340".to_string()])
             .expect("could not parse input");
-        assert_eq!(part_one(&trans), 23);
+        assert_eq!(part_one(&trans).unwrap(), 23);
 
         trans = parse_input(vec!["A0016C880162017C3686B18A3D4780".to_string()])
             .expect("could not parse input");
-        assert_eq!(part_one(&trans), 31);
+        assert_eq!(part_one(&trans).unwrap(), 31);
     }
 
     #[test]
     fn part_two_test() {
         let mut trans = parse_input(vec!["D2FE28".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 2021);
+        assert_eq!(part_two(&trans).unwrap(), 2021);
 
         trans = parse_input(vec!["C200B40A82".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 3);
+        assert_eq!(part_two(&trans).unwrap(), 3);
 
         trans = parse_input(vec!["04005AC33890".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 54);
+        assert_eq!(part_two(&trans).unwrap(), 54);
 
         trans = parse_input(vec!["880086C3E88112".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 7);
+        assert_eq!(part_two(&trans).unwrap(), 7);
 
         trans = parse_input(vec!["CE00C43D881120".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 9);
+        assert_eq!(part_two(&trans).unwrap(), 9);
 
         trans = parse_input(vec!["D8005AC2A8F0".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 1);
+        assert_eq!(part_two(&trans).unwrap(), 1);
 
         trans = parse_input(vec!["F600BC2D8F".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 0);
+        assert_eq!(part_two(&trans).unwrap(), 0);
 
         trans = parse_input(vec!["9C005AC2F8F0".to_string()]).expect("could not parse input");
-        assert_eq!(part_two(&trans), 0);
+        assert_eq!(part_two(&trans).unwrap(), 0);
 
         trans = parse_input(vec!["9C0141080250320F1802104A08".to_string()])
             .expect("could not parse input");
-        assert_eq!(part_two(&trans), 1);
+        assert_eq!(part_two(&trans).unwrap(), 1);
     }
 
     #[test]
@@ -342,10 +1786,423 @@ mod tests {
         assert_eq!(trans.slice(0, 16), Some(0b1010000000000001));
         assert_eq!(trans.slice(110, 5), Some(0b11100));
         assert_eq!(trans.slice(110, 10), Some(0b1110000000));
-        assert_eq!(trans.slice(0, 17), None);
+        assert_eq!(trans.slice(0, 17), Some(0b10100000000000010));
+        assert_eq!(trans.slice(30, 64), Some(0x0058_805F_0DA1_AC62));
+        assert_eq!(trans.slice(0, 65), None);
         assert_eq!(trans.slice(0, 0), None);
         assert_eq!(trans.slice(5, 0), None);
         assert_eq!(trans.slice(120, 1), None);
         assert_eq!(trans.slice(110, 11), None);
     }
+
+    #[test]
+    fn encode_test() {
+        let literal = Packet::literal(6, 2021);
+        assert_eq!(literal.encode().unwrap().to_hex(), "D2FE28");
+
+        let bits = Packet::operator(
+            1,
+            6,
+            LengthType::Bits,
+            vec![Packet::literal(6, 10), Packet::literal(2, 20)],
+        );
+        assert!("38006F45291200".starts_with(&bits.encode().unwrap().to_hex()));
+
+        let packets = Packet::operator(
+            7,
+            3,
+            LengthType::Packets,
+            vec![
+                Packet::literal(2, 1),
+                Packet::literal(4, 2),
+                Packet::literal(1, 3),
+            ],
+        );
+        assert!("EE00D40C823060".starts_with(&packets.encode().unwrap().to_hex()));
+
+        assert_eq!(Packet::literal(0, 0).encode().unwrap().len(), 11);
+        assert!(Packet::literal(8, 1).encode().is_err());
+        assert!(Packet::operator(0, 4, LengthType::Bits, vec![])
+            .encode()
+            .is_err());
+        assert!(
+            Packet::operator(0, 0, LengthType::Packets, vec![Packet::literal(0, 0); 2048])
+                .encode()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn round_trip_test() {
+        for hex in [
+            "D2FE28",
+            "8A004A801A8002F478",
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+            "9C0141080250320F1802104A08",
+        ] {
+            let packet = hex
+                .parse::<Transmission>()
+                .expect("could not parse input")
+                .parse_packet()
+                .expect("could not parse packet");
+            let encoded = packet.encode().expect("could not encode packet");
+
+            assert!(hex.starts_with(&encoded.to_hex()));
+            assert_eq!(encoded.parse_packet().unwrap(), packet);
+        }
+
+        let packet = Packet::operator(
+            5,
+            0,
+            LengthType::Bits,
+            vec![
+                Packet::literal(1, u64::MAX),
+                Packet::operator(
+                    2,
+                    7,
+                    LengthType::Packets,
+                    vec![Packet::literal(3, 0), Packet::literal(4, 15)],
+                ),
+            ],
+        );
+        let hex = packet.encode().unwrap().to_hex();
+        let decoded = hex
+            .parse::<Transmission>()
+            .unwrap()
+            .parse_packet()
+            .unwrap();
+        assert_eq!(decoded, packet);
+    }
+
+    #[test]
+    fn render_test() {
+        let packet = Packet::operator(
+            0,
+            1,
+            LengthType::Packets,
+            vec![
+                Packet::operator(
+                    0,
+                    0,
+                    LengthType::Bits,
+                    vec![Packet::literal(0, 3), Packet::literal(0, 4)],
+                ),
+                Packet::operator(
+                    0,
+                    2,
+                    LengthType::Bits,
+                    vec![Packet::literal(0, 5), Packet::literal(0, 9)],
+                ),
+            ],
+        );
+        assert_eq!(packet.to_string(), "(3 + 4) * min(5, 9)");
+        assert_eq!(packet.sexpr(), "(* (+ 3 4) (min 5 9))");
+
+        // a single child sum renders as its child, so the sum inside still needs brackets
+        let sum = Packet::operator(
+            0,
+            0,
+            LengthType::Bits,
+            vec![Packet::literal(0, 3), Packet::literal(0, 4)],
+        );
+        let packet = Packet::operator(
+            0,
+            1,
+            LengthType::Bits,
+            vec![
+                Packet::literal(0, 2),
+                Packet::operator(0, 0, LengthType::Packets, vec![sum]),
+            ],
+        );
+        assert_eq!(packet.to_string(), "2 * (3 + 4)");
+        assert_eq!(packet.eval().unwrap(), 14);
+
+        let trans = parse_input(vec!["9C0141080250320F1802104A08".to_string()])
+            .expect("could not parse input");
+        let packet = trans.parse_packet().unwrap();
+        assert_eq!(packet.to_string(), "(1 + 3) == (2 * 2)");
+        assert_eq!(
+            packet.outline(),
+            "eq (version 4, type 7, bits 0..102)
+  sum (version 2, type 0, bits 22..62)
+    literal 1 (version 2, type 4, bits 40..51)
+    literal 3 (version 4, type 4, bits 51..62)
+  product (version 6, type 1, bits 62..102)
+    literal 2 (version 0, type 4, bits 80..91)
+    literal 2 (version 2, type 4, bits 91..102)"
+        );
+    }
+
+    #[test]
+    fn accessors_test() {
+        let trans = parse_input(vec!["38006F45291200".to_string()]).expect("could not parse input");
+        let packet = trans.parse_packet().unwrap();
+
+        assert_eq!(packet.version(), 1);
+        assert_eq!(packet.type_id(), 6);
+        assert_eq!(packet.length_type(), Some(LengthType::Bits));
+        assert_eq!(packet.bits(), Some(0..49));
+        assert_eq!(
+            packet.children().iter().map(|p| p.value()).collect::<Vec<_>>(),
+            vec![Some(10), Some(20)]
+        );
+        assert!(packet.children()[0].children().is_empty());
+        assert_eq!(packet.children()[1].bits(), Some(33..49));
+    }
+
+    #[test]
+    fn stream_test() {
+        for hex in [
+            "D2FE28",
+            "8A004A801A8002F478",
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+            "9C0141080250320F1802104A08",
+            "F600BC2D8F",
+        ] {
+            let trans = parse_input(vec![hex.to_string()]).expect("could not parse input");
+            let expected = (part_one(&trans).unwrap(), part_two(&trans).unwrap());
+
+            assert_eq!(stream_summary(Decoder::hex(hex.as_bytes())).unwrap(), expected);
+            let line = format!("{}\n", hex);
+            assert_eq!(stream_summary(Decoder::hex(line.as_bytes())).unwrap(), expected);
+
+            let raw = (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect::<Vec<u8>>();
+            assert_eq!(stream_summary(Decoder::raw(raw.as_slice())).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn stream_events_test() {
+        let events = Decoder::hex("38006F45291200".as_bytes())
+            .collect::<Result<Vec<Event>, AocError>>()
+            .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                Event::Enter {
+                    version: 1,
+                    type_id: 6,
+                    length_type: LengthType::Bits,
+                    offset: 0
+                },
+                Event::Literal {
+                    version: 6,
+                    number: 10,
+                    offset: 22
+                },
+                Event::Literal {
+                    version: 2,
+                    number: 20,
+                    offset: 33
+                },
+                Event::Exit { type_id: 6 },
+            ]
+        );
+
+        assert!(stream_summary(Decoder::hex("38006F45".as_bytes())).is_err());
+        assert!(stream_summary(Decoder::hex("38006X45291200".as_bytes())).is_err());
+    }
+
+    #[test]
+    fn overflow_test() {
+        let product = Packet::operator(
+            0,
+            1,
+            LengthType::Packets,
+            vec![Packet::literal(0, u64::MAX), Packet::literal(0, 3)],
+        );
+        let e = product.eval().unwrap_err();
+        assert_eq!(e.to_string(), "no solution: product overflows 64 bits");
+        assert_eq!(product.eval_exact().unwrap().to_string(), "55340232221128654845");
+
+        // the comparison fits even though its terms do not
+        let compare = Packet::operator(0, 5, LengthType::Bits, vec![product.clone(), Packet::literal(0, 1)]);
+        assert!(compare.eval().is_err());
+        assert_eq!(compare.eval_exact().unwrap(), Number::Small(1));
+
+        let hex = Packet::operator(0, 0, LengthType::Bits, vec![product])
+            .encode()
+            .unwrap()
+            .to_hex();
+        let e = stream_summary(Decoder::hex(hex.as_bytes())).unwrap_err();
+        assert_eq!(e.to_string(), "no solution: product overflows 64 bits");
+    }
+
+    #[test]
+    fn wide_literal_test() {
+        let mut big = BigUint::from(u64::MAX);
+        big.mul_add(16, 0xF);
+        let packet = Packet::wide_literal(3, Number::from(big));
+
+        let trans = packet.encode().unwrap();
+        let decoded = trans.parse_packet().unwrap();
+        assert_eq!(decoded, packet);
+        assert_eq!(decoded.value(), None);
+        assert_eq!(decoded.to_string(), "295147905179352825855");
+        assert!(decoded.eval().is_err());
+        assert_eq!(decoded.eval_exact().unwrap().to_string(), "295147905179352825855");
+
+        let e = stream_summary(Decoder::hex(trans.to_hex().as_bytes())).unwrap_err();
+        assert_eq!(e.to_string(), "parse error: literal does not fit in 64 bits at bit 86");
+    }
+
+    #[test]
+    fn decode_error_test() {
+        let trans = Transmission::from_str("38006F45").unwrap();
+        let e = trans.decode(Mode::Strict).unwrap_err();
+        assert_eq!(
+            e,
+            DecodeError::new(DecodeErrorKind::Truncated(Field::LiteralGroup), 28, vec![0])
+        );
+        assert_eq!(e.to_string(), "could not parse literal group at bit 28 in subpacket 0");
+        assert_eq!(
+            part_one(&trans).unwrap_err().to_string(),
+            "parse error: could not parse literal group at bit 28 in subpacket 0"
+        );
+
+        let trans = Transmission::from_str("D2FE2F").unwrap();
+        let e = trans.decode(Mode::Strict).unwrap_err();
+        assert_eq!(e, DecodeError::new(DecodeErrorKind::Trailing, 21, vec![]));
+
+        let decoded = trans.decode(Mode::Lenient).unwrap();
+        assert_eq!(decoded.packet.value(), Some(2021));
+        assert_eq!(decoded.diagnostics, vec![e]);
+        assert!(stream_summary(Decoder::hex("D2FE2F".as_bytes())).is_err());
+    }
+
+    #[test]
+    fn arity_test() {
+        let children = vec![Packet::literal(0, 1), Packet::literal(0, 2), Packet::literal(0, 3)];
+        let trans = Packet::operator(0, 5, LengthType::Packets, children).encode().unwrap();
+
+        let e = trans.decode(Mode::Strict).unwrap_err();
+        assert_eq!(e.to_string(), "gt expects exactly 2 subpackets, found 3 at bit 0");
+        let e = stream_summary(Decoder::hex(trans.to_hex().as_bytes())).unwrap_err();
+        assert_eq!(e.to_string(), "parse error: gt expects exactly 2 subpackets, found 3 at bit 0");
+
+        let decoded = trans.decode(Mode::Lenient).unwrap();
+        assert_eq!(decoded.diagnostics.len(), 1);
+        assert!(decoded.packet.eval().is_err());
+
+        let nested = Packet::operator(0, 0, LengthType::Bits, vec![Packet::operator(0, 2, LengthType::Bits, vec![])]);
+        let e = nested.encode().unwrap().decode(Mode::Strict).unwrap_err();
+        assert_eq!(e.to_string(), "min expects at least 1 subpacket, found 0 at bit 22 in subpacket 0");
+    }
+
+    #[test]
+    fn lenient_test() {
+        // a sum whose bit length leaves room for a second, truncated subpacket
+        let trans = Transmission::from_str("00003C4080").unwrap();
+
+        let e = trans.decode(Mode::Strict).unwrap_err();
+        assert_eq!(e, DecodeError::new(DecodeErrorKind::Truncated(Field::BitLength), 40, vec![1]));
+
+        let decoded = trans.decode(Mode::Lenient).unwrap();
+        assert_eq!(decoded.diagnostics, vec![e]);
+        assert_eq!(decoded.packet.children().len(), 1);
+        assert_eq!(decoded.packet.eval().unwrap(), 1);
+    }
+
+    #[test]
+    fn operators_test() {
+        let mut operators = Operators::STANDARD;
+        operators
+            .register(
+                5,
+                Operator {
+                    name: "sub",
+                    arity: Arity::AtLeast(1),
+                    apply: |a, b| a.checked_sub(b).ok_or("underflows"),
+                },
+            )
+            .unwrap();
+        operators
+            .register(
+                7,
+                Operator {
+                    name: "xor",
+                    arity: Arity::Exactly(2),
+                    apply: |a, b| Ok(a ^ b),
+                },
+            )
+            .unwrap();
+
+        let packet = Packet::operator(
+            0,
+            5,
+            LengthType::Packets,
+            vec![
+                Packet::literal(0, 20),
+                Packet::literal(0, 5),
+                Packet::operator(0, 7, LengthType::Bits, vec![Packet::literal(0, 6), Packet::literal(0, 3)]),
+            ],
+        );
+        assert_eq!(packet.eval_with(&operators).unwrap(), 10);
+        assert!(packet.eval().is_err());
+        assert_eq!(packet.infix_with(&operators).to_string(), "sub(20, 5, xor(6, 3))");
+        assert_eq!(packet.sexpr_with(&operators), "(sub 20 5 (xor 6 3))");
+        assert_eq!(packet.name_with(&operators), "sub");
+        assert!(packet.outline_with(&operators).starts_with("sub (version 0, type 5)"));
+
+        let trans = packet.encode().unwrap();
+        assert!(trans.decode(Mode::Strict).is_err());
+        assert_eq!(trans.decode_with(Mode::Strict, &operators).unwrap().packet, packet);
+
+        let mut visitors = (VersionSum::default(), Evaluator::new(operators));
+        Decoder::hex(trans.to_hex().as_bytes())
+            .with_operators(operators)
+            .accept(&mut visitors)
+            .unwrap();
+        assert_eq!(visitors.1.value(), Some(10));
+
+        let e = Packet::operator(0, 5, LengthType::Packets, vec![Packet::literal(0, 1), Packet::literal(0, 2)])
+            .eval_with(&operators)
+            .unwrap_err();
+        assert_eq!(e.to_string(), "no solution: sub underflows");
+
+        let op = Operator {
+            name: "none",
+            arity: Arity::Exactly(0),
+            apply: |_, _| Err("has no value"),
+        };
+        assert!(operators.register(4, op).is_err());
+        assert!(operators.register(8, op).is_err());
+        assert!(operators.register(0, op).is_err());
+    }
+
+    #[test]
+    fn constructors_test() {
+        let hex = Transmission::from_str("D2FE28").unwrap();
+        assert_eq!(Transmission::from([0xD2, 0xFE, 0x28].as_slice()), hex);
+        assert_eq!(Transmission::from_base64("0v4o").unwrap(), hex);
+
+        let bits = Transmission::from_bits("110100101111111000101").unwrap();
+        assert_eq!(bits.len(), 21);
+        assert_eq!(bits.decode(Mode::Strict).unwrap().packet.value(), Some(2021));
+
+        for (hex, base64) in [
+            ("38006F45291200", "OABvRSkSAA=="),
+            ("38006F45291200", "OABvRSkSAA"),
+            ("8A004A801A8002F478", "igBKgBqAAvR4"),
+        ] {
+            assert_eq!(
+                Transmission::from_base64(base64).unwrap(),
+                Transmission::from_str(hex).unwrap()
+            );
+        }
+
+        let e = Transmission::from_bits("0102").unwrap_err();
+        assert_eq!(e.to_string(), "parse error at column 4: could not parse bit in \"2\"");
+        assert!(Transmission::from_base64("0v4o!").is_err());
+        assert!(Transmission::from_base64("0v4oA").is_err());
+        assert!(Transmission::from_base64("0v===").is_err());
+    }
 }
@@ -0,0 +1,226 @@
+use std::{error::Error, fmt::Display, io};
+
+pub type Result<T> = std::result::Result<T, AocError>;
+
+/// Errors that can occur while loading, parsing or solving a puzzle
+#[derive(Debug)]
+pub enum AocError {
+    /// The input could not be read
+    Io(io::Error),
+
+    /// The input could not be parsed. Line and column are 1-based, when known
+    Parse {
+        line: Option<usize>,
+        column: Option<usize>,
+        text: String,
+        reason: String,
+    },
+
+    /// The input was parsed, but has no answer under the puzzle rules
+    NoSolution(String),
+
+    /// A value built in code breaks the rules of the format it is being written in
+    Invalid(String),
+}
+
+impl AocError {
+    pub fn parse(reason: impl Into<String>, text: impl Into<String>) -> Self {
+        Self::Parse {
+            line: None,
+            column: None,
+            text: text.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn no_solution(reason: impl Into<String>) -> Self {
+        Self::NoSolution(reason.into())
+    }
+
+    pub fn invalid(reason: impl Into<String>) -> Self {
+        Self::Invalid(reason.into())
+    }
+
+    /// Attaches a 1-based line number to a parse error, keeping any line that is already known.
+    /// Other errors are returned unchanged
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Self::Parse {
+                line: None,
+                column,
+                text,
+                reason,
+            } => Self::Parse {
+                line: Some(line),
+                column,
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// Attaches a 1-based column number to a parse error, keeping any column that is already
+    /// known. Other errors are returned unchanged
+    pub fn at_column(self, column: usize) -> Self {
+        match self {
+            Self::Parse {
+                line,
+                column: None,
+                text,
+                reason,
+            } => Self::Parse {
+                line,
+                column: Some(column),
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// Shifts the line number of a parse error by the given offset, for errors raised while parsing
+    /// a section that does not start at the top of the input
+    pub fn offset_line(self, offset: usize) -> Self {
+        match self {
+            Self::Parse {
+                line: Some(line),
+                column,
+                text,
+                reason,
+            } => Self::Parse {
+                line: Some(line + offset),
+                column,
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    /// Shifts the column number of a parse error by the given offset, for errors raised while
+    /// parsing a token that does not start at the beginning of the line
+    pub fn offset_column(self, offset: usize) -> Self {
+        match self {
+            Self::Parse {
+                line,
+                column,
+                text,
+                reason,
+            } => Self::Parse {
+                line,
+                column: Some(column.unwrap_or(1) + offset),
+                text,
+                reason,
+            },
+            other => other,
+        }
+    }
+
+    pub fn is_parse(&self) -> bool {
+        matches!(self, Self::Parse { .. })
+    }
+
+    pub fn is_no_solution(&self) -> bool {
+        matches!(self, Self::NoSolution(_))
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {}", e),
+            Self::Parse {
+                line,
+                column,
+                text,
+                reason,
+            } => {
+                write!(f, "parse error")?;
+                match (line, column) {
+                    (Some(line), Some(column)) => write!(f, " at line {}, column {}", line, column)?,
+                    (Some(line), None) => write!(f, " at line {}", line)?,
+                    (None, Some(column)) => write!(f, " at column {}", column)?,
+                    (None, None) => (),
+                }
+                write!(f, ": {}", reason)?;
+                if !text.is_empty() {
+                    write!(f, " in {:?}", text)?;
+                }
+                Ok(())
+            }
+            Self::NoSolution(reason) => write!(f, "no solution: {}", reason),
+            Self::Invalid(reason) => write!(f, "invalid: {}", reason),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Parses every line with the given parser, attaching the 1-based line number to any failure
+pub fn parse_lines<T, F>(lines: &[String], parser: F) -> Result<Vec<T>>
+where
+    F: Fn(&str) -> Result<T>,
+{
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parser(line).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        let e = AocError::parse("expected 2 numbers", "1,").at_line(3).at_column(2);
+        assert_eq!(
+            e.to_string(),
+            "parse error at line 3, column 2: expected 2 numbers in \"1,\""
+        );
+
+        let e = AocError::parse("invalid token", "x").at_line(1).at_line(5);
+        assert_eq!(e.to_string(), "parse error at line 1: invalid token in \"x\"");
+
+        let e = AocError::parse("expected at least 1 line", "");
+        assert_eq!(e.to_string(), "parse error: expected at least 1 line");
+
+        let e = AocError::no_solution("no winning board");
+        assert_eq!(e.to_string(), "no solution: no winning board");
+    }
+
+    #[test]
+    fn parse_lines_test() {
+        let lines = vec!["1".to_string(), "2".to_string(), "x".to_string()];
+        let parser = |s: &str| {
+            s.parse::<u64>()
+                .map_err(|_| AocError::parse("could not parse integer", s))
+        };
+
+        let actual = parse_lines(&lines, parser);
+        match actual {
+            Err(AocError::Parse { line, text, .. }) => {
+                assert_eq!(line, Some(3));
+                assert_eq!(text, "x");
+            }
+            _ => panic!("expected a parse error"),
+        }
+
+        assert_eq!(parse_lines(&lines[..2], parser).unwrap(), vec![1, 2]);
+    }
+}
@@ -1,4 +1,10 @@
-use std::{convert::TryFrom, ops::{Index, IndexMut}};
+use std::{
+    convert::TryFrom,
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::{self, AocError};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Coordinate(i64, i64);
@@ -96,37 +102,35 @@ impl Coordinate {
     }
 }
 
-
-#[derive(Debug, Clone)]
-pub struct Grid<T>
-{
-    grid: Vec<Vec<T>>,
+/// A dense 2D grid of cells, indexed by `Coordinate`. Rows are numbered from the top, and columns
+/// from the left
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
     pub n: usize,
     pub m: usize,
 }
 
-impl<T> TryFrom<Vec<String>> for Grid<T> where T: TryFrom<char> {
-    type Error = T::Error;
+impl<T> TryFrom<Vec<String>> for Grid<T>
+where
+    T: TryFrom<char, Error = AocError>,
+{
+    type Error = AocError;
 
     fn try_from(lines: Vec<String>) -> Result<Self, Self::Error> {
-        let grid = lines
-            .iter()
-            .map(|line| {
-                line.chars()
-                    .map(|c| T::try_from(c))
-                    .collect::<Result<Vec<T>, T::Error>>()
-            })
-            .collect::<Result<Vec<Vec<T>>, T::Error>>()?;
-
-        Ok(grid.into())
+        Self::parse(&lines, T::try_from)
     }
 }
 
 impl<T> From<Vec<Vec<T>>> for Grid<T> {
     fn from(grid: Vec<Vec<T>>) -> Self {
         let n = grid.len();
-        let m = grid[0].len();
-        Self { grid, n, m }
+        let m = grid.first().map(|row| row.len()).unwrap_or(0);
+        Self {
+            cells: grid.into_iter().flatten().collect(),
+            n,
+            m,
+        }
     }
 }
 
@@ -134,26 +138,239 @@ impl<T> Index<Coordinate> for Grid<T> {
     type Output = T;
 
     fn index(&self, idx: Coordinate) -> &Self::Output {
-        &self.grid[idx.0 as usize][idx.1 as usize]
+        match self.offset(idx) {
+            Some(offset) => &self.cells[offset],
+            None => panic!("{:?} is out of bounds", idx),
+        }
     }
 }
 
 impl<T> IndexMut<Coordinate> for Grid<T> {
     fn index_mut(&mut self, idx: Coordinate) -> &mut Self::Output {
-        &mut self.grid[idx.0 as usize][idx.1 as usize]
+        match self.offset(idx) {
+            Some(offset) => &mut self.cells[offset],
+            None => panic!("{:?} is out of bounds", idx),
+        }
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.cells.chunks(self.m.max(1)).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
     }
 }
 
-impl<T> Grid<T> where T: Copy {
+impl<T> Grid<T>
+where
+    T: Clone,
+{
     pub fn new(n: usize, m: usize, default: T) -> Self {
         Self {
-            grid: vec![vec![default; m]; n],
+            cells: vec![default; n * m],
             n,
             m,
         }
     }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid by calling the given function with the coordinate of every cell
+    pub fn from_fn(n: usize, m: usize, mut f: impl FnMut(Coordinate) -> T) -> Self {
+        let cells = (0..n)
+            .flat_map(|i| (0..m).map(move |j| Coordinate::from((i, j))))
+            .map(&mut f)
+            .collect();
+        Self { cells, n, m }
+    }
+
+    /// Parses a grid with one cell per char, attaching the line and column of any failure.
+    /// Every row must have the same, non-zero length
+    pub fn parse<F>(lines: &[String], parser: F) -> Result<Self, AocError>
+    where
+        F: Fn(char) -> Result<T, AocError>,
+    {
+        if lines.is_empty() {
+            return Err(AocError::parse("expected at least 1 row", ""));
+        }
+
+        let m = lines[0].chars().count();
+        if m == 0 {
+            return Err(AocError::parse("expected at least 1 column", "").at_line(1));
+        }
+
+        let rows = error::parse_lines(lines, |line| {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(j, c)| parser(c).map_err(|e| e.at_column(j + 1)))
+                .collect::<Result<Vec<T>, AocError>>()?;
+
+            if row.len() != m {
+                return Err(AocError::parse("expected all rows to have the same length", line));
+            }
+
+            Ok(row)
+        })?;
+
+        Ok(rows.into())
+    }
 
     pub fn is_in_bounds(&self, coord: Coordinate) -> bool {
         (0..self.n as i64).contains(&coord.0) && (0..self.m as i64).contains(&coord.1)
     }
+
+    pub fn get(&self, coord: Coordinate) -> Option<&T> {
+        self.offset(coord).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, coord: Coordinate) -> Option<&mut T> {
+        self.offset(coord).map(move |offset| &mut self.cells[offset])
+    }
+
+    /// Returns an iterator over the coordinates of every cell, row by row
+    pub fn coords(&self) -> impl Iterator<Item = Coordinate> {
+        let m = self.m;
+        (0..self.n).flat_map(move |i| (0..m).map(move |j| Coordinate::from((i, j))))
+    }
+
+    /// Returns an iterator over every cell, row by row
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Returns an iterator over every cell along with its coordinate, row by row
+    pub fn cells(&self) -> impl Iterator<Item = (Coordinate, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    /// Returns an iterator over every cell along with its coordinate, row by row
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = (Coordinate, &mut T)> {
+        self.coords().zip(self.cells.iter_mut())
+    }
+
+    /// Returns the north, south, east and west neighbours of a coordinate that are in bounds
+    pub fn cardinal_neighbours(&self, coord: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        self.in_bounds(coord.cardinal_neighbours())
+    }
+
+    /// Returns all 8 neighbours of a coordinate that are in bounds, including diagonals
+    pub fn neighbours(&self, coord: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        self.in_bounds(coord.neighbours())
+    }
+
+    /// Creates a grid of the same shape by applying the given function to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            n: self.n,
+            m: self.m,
+        }
+    }
+
+    fn in_bounds<const N: usize>(
+        &self,
+        coords: [Coordinate; N],
+    ) -> impl Iterator<Item = Coordinate> + '_ {
+        IntoIterator::into_iter(coords).filter(move |coord| self.is_in_bounds(*coord))
+    }
+
+    fn offset(&self, coord: Coordinate) -> Option<usize> {
+        if self.is_in_bounds(coord) {
+            Some(coord.0 as usize * self.m + coord.1 as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parses a single decimal digit, for grids of digits
+pub fn digit(c: char) -> Result<usize, AocError> {
+    c.to_digit(10)
+        .map(|digit| digit as usize)
+        .ok_or_else(|| AocError::parse("could not parse digit", c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<usize> {
+        let lines = vec!["123".to_string(), "456".to_string()];
+        Grid::parse(&lines, digit).expect("could not parse input")
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(example(), Grid::from(vec![vec![1, 2, 3], vec![4, 5, 6]]));
+
+        let lines = vec!["12".to_string(), "3x".to_string()];
+        let e = Grid::parse(&lines, digit).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "parse error at line 2, column 2: could not parse digit in \"x\""
+        );
+
+        let lines = vec!["12".to_string(), "3".to_string()];
+        assert!(Grid::parse(&lines, digit).is_err());
+        assert!(Grid::parse(&[], digit).is_err());
+        assert!(Grid::parse(&["".to_string()], digit).is_err());
+        assert!(Grid::parse(&["".to_string(), "".to_string()], digit).is_err());
+    }
+
+    #[test]
+    fn get_test() {
+        let grid = example();
+
+        assert_eq!(grid.get((1_usize, 2_usize).into()), Some(&6));
+        assert_eq!(grid.get((2_usize, 0_usize).into()), None);
+        assert_eq!(grid.get((0_i64, -1_i64).into()), None);
+        assert_eq!(grid[Coordinate::from((0_usize, 1_usize))], 2);
+    }
+
+    #[test]
+    fn cells_test() {
+        let grid = example();
+
+        assert_eq!(grid.values().sum::<usize>(), 21);
+        assert_eq!(
+            grid.cells().find(|(_, v)| **v == 5).map(|(c, _)| c),
+            Some((1_usize, 1_usize).into())
+        );
+        assert_eq!(grid.coords().count(), 6);
+    }
+
+    #[test]
+    fn neighbours_test() {
+        let grid = example();
+        let corner = Coordinate::from((0_usize, 0_usize));
+
+        let mut actual: Vec<usize> = grid.cardinal_neighbours(corner).map(|c| grid[c]).collect();
+        actual.sort_unstable();
+        assert_eq!(actual, vec![2, 4]);
+
+        let mut actual: Vec<usize> = grid.neighbours(corner).map(|c| grid[c]).collect();
+        actual.sort_unstable();
+        assert_eq!(actual, vec![2, 4, 5]);
+
+        assert_eq!(grid.neighbours((1_usize, 1_usize).into()).count(), 5);
+    }
+
+    #[test]
+    fn map_and_display_test() {
+        let grid = example().map(|v| if v % 2 == 0 { '#' } else { '.' });
+
+        assert_eq!(grid.to_string(), ".#.\n#.#");
+    }
 }
@@ -0,0 +1,270 @@
+use std::io::{self, Write};
+
+/// The image formats a `BitmapWriter` can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary portable bitmap (P4)
+    Pbm,
+
+    /// 1 bit greyscale PNG. The image data is stored uncompressed, which keeps the encoder small
+    /// at the cost of larger files
+    Png,
+}
+
+/// Writes a black and white image one row at a time, so that large images never have to be held
+/// in memory. Rows are packed 8 pixels to a byte, most significant bit first, with 1 for black
+pub struct BitmapWriter<W: Write> {
+    out: W,
+    format: ImageFormat,
+    width: usize,
+    height: usize,
+    rows: usize,
+    adler: Adler32,
+}
+
+impl<W: Write> BitmapWriter<W> {
+    const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+    /// The most data a single uncompressed deflate block can hold
+    const MAX_STORED_BLOCK: usize = 0xffff;
+
+    /// Starts an image of the given size by writing its header
+    pub fn new(mut out: W, format: ImageFormat, width: usize, height: usize) -> io::Result<Self> {
+        match format {
+            ImageFormat::Pbm => write!(out, "P4\n{} {}\n", width, height)?,
+            ImageFormat::Png => {
+                if width == 0 || height == 0 || width > u32::MAX as usize || height > u32::MAX as usize {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("a png cannot be {}x{}", width, height),
+                    ));
+                }
+
+                out.write_all(&Self::PNG_SIGNATURE)?;
+                let mut header = Vec::with_capacity(13);
+                header.extend_from_slice(&(width as u32).to_be_bytes());
+                header.extend_from_slice(&(height as u32).to_be_bytes());
+                // bit depth 1, greyscale, deflate, no filtering, no interlacing
+                header.extend_from_slice(&[1, 0, 0, 0, 0]);
+                write_chunk(&mut out, b"IHDR", &header)?;
+            }
+        }
+
+        Ok(Self {
+            out,
+            format,
+            width,
+            height,
+            rows: 0,
+            adler: Adler32::default(),
+        })
+    }
+
+    /// The number of bytes in each packed row
+    pub fn stride(&self) -> usize {
+        self.width.div_ceil(8)
+    }
+
+    pub fn write_row(&mut self, row: &[u8]) -> io::Result<()> {
+        if row.len() != self.stride() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected a row of {} bytes, got {}", self.stride(), row.len()),
+            ));
+        }
+        if self.rows == self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected at most {} rows", self.height),
+            ));
+        }
+
+        match self.format {
+            ImageFormat::Pbm => self.out.write_all(row)?,
+            ImageFormat::Png => {
+                // png uses 0 for black, and every row starts with its filter type
+                let mut data = Vec::with_capacity(row.len() + 1);
+                data.push(0);
+                data.extend(row.iter().map(|byte| !byte));
+                self.adler.update(&data);
+
+                // each row goes in its own chunk, as stored blocks of the one zlib stream
+                let mut chunk = Vec::with_capacity(data.len() + 8);
+                if self.rows == 0 {
+                    chunk.extend_from_slice(&[0x78, 0x01]);
+                }
+                for block in data.chunks(Self::MAX_STORED_BLOCK) {
+                    stored_block(&mut chunk, block, false);
+                }
+                write_chunk(&mut self.out, b"IDAT", &chunk)?;
+            }
+        }
+
+        self.rows += 1;
+        Ok(())
+    }
+
+    /// Finishes the image, which must have had every row written, and returns the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.rows != self.height {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected {} rows, got {}", self.height, self.rows),
+            ));
+        }
+
+        if self.format == ImageFormat::Png {
+            let mut chunk = Vec::new();
+            stored_block(&mut chunk, &[], true);
+            chunk.extend_from_slice(&self.adler.finish().to_be_bytes());
+            write_chunk(&mut self.out, b"IDAT", &chunk)?;
+            write_chunk(&mut self.out, b"IEND", &[])?;
+        }
+
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+fn write_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(crc32(0, kind), data);
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// Appends an uncompressed deflate block, which can hold up to 65535 bytes
+fn stored_block(out: &mut Vec<u8>, data: &[u8], last: bool) {
+    let len = data.len() as u16;
+    out.push(last as u8);
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&(!len).to_le_bytes());
+    out.extend_from_slice(data);
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0_u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Continues the CRC-32 used by png (and zip) from a previous value, which is 0 to start
+fn crc32(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for byte in bytes {
+        crc = CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// The running checksum at the end of a zlib stream
+#[derive(Debug, Clone, Copy)]
+struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self { a: 1, b: 0 }
+    }
+}
+
+impl Adler32 {
+    const MODULUS: u32 = 65521;
+
+    fn update(&mut self, bytes: &[u8]) {
+        // the sums cannot overflow within this many bytes before being reduced
+        for chunk in bytes.chunks(5552) {
+            for byte in chunk {
+                self.a += *byte as u32;
+                self.b += self.a;
+            }
+            self.a %= Self::MODULUS;
+            self.b %= Self::MODULUS;
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_test() {
+        assert_eq!(crc32(0, b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(crc32(0, b"1234"), b"56789"), 0xcbf4_3926);
+
+        let mut adler = Adler32::default();
+        adler.update(b"Wikipedia");
+        assert_eq!(adler.finish(), 0x11e6_0398);
+    }
+
+    #[test]
+    fn pbm_test() {
+        let mut writer = BitmapWriter::new(Vec::new(), ImageFormat::Pbm, 10, 2).unwrap();
+        writer.write_row(&[0b1000_0000, 0b0100_0000]).unwrap();
+        writer.write_row(&[0b0000_0001, 0b0000_0000]).unwrap();
+        assert!(writer.write_row(&[0]).is_err());
+
+        let mut expected = b"P4\n10 2\n".to_vec();
+        expected.extend_from_slice(&[0b1000_0000, 0b0100_0000, 0b0000_0001, 0b0000_0000]);
+        assert_eq!(writer.finish().unwrap(), expected);
+    }
+
+    #[test]
+    fn png_test() {
+        let mut writer = BitmapWriter::new(Vec::new(), ImageFormat::Png, 3, 2).unwrap();
+        writer.write_row(&[0b1010_0000]).unwrap();
+        assert!(writer.write_row(&[0, 0]).is_err());
+        let unfinished = BitmapWriter::new(Vec::new(), ImageFormat::Png, 3, 2).unwrap();
+        assert!(unfinished.finish().is_err());
+        writer.write_row(&[0b0100_0000]).unwrap();
+        let png = writer.finish().unwrap();
+
+        assert_eq!(png[..8], BitmapWriter::<Vec<u8>>::PNG_SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 3, 0, 0, 0, 2]);
+        assert_eq!(png[png.len() - 12..], [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]);
+
+        // pull the zlib stream back out of the chunks, and the rows out of its stored blocks
+        let mut zlib = Vec::new();
+        let mut i = 8;
+        while i < png.len() {
+            let len = u32::from_be_bytes([png[i], png[i + 1], png[i + 2], png[i + 3]]) as usize;
+            let kind = &png[i + 4..i + 8];
+            let data = &png[i + 8..i + 8 + len];
+            let crc = &png[i + 8 + len..i + 12 + len];
+            assert_eq!(crc, crc32(crc32(0, kind), data).to_be_bytes());
+            if kind == b"IDAT" {
+                zlib.extend_from_slice(data);
+            }
+            i += len + 12;
+        }
+
+        assert_eq!(zlib[..2], [0x78, 0x01]);
+        assert_eq!(zlib[2..7], [0, 2, 0, 0xfd, 0xff]);
+        assert_eq!(zlib[7..9], [0, 0b0101_1111]);
+        assert_eq!(zlib[14..16], [0, 0b1011_1111]);
+        assert_eq!(zlib[16..21], [1, 0, 0, 0xff, 0xff]);
+
+        let mut adler = Adler32::default();
+        adler.update(&[0, 0b0101_1111, 0, 0b1011_1111]);
+        assert_eq!(zlib[21..], adler.finish().to_be_bytes());
+    }
+}
@@ -1,5 +1,12 @@
 pub mod utils;
+pub mod error;
+pub mod answers;
 pub mod grid;
+pub mod search;
+pub mod bigint;
+pub mod bits;
+pub mod image;
+pub mod solution;
 
 pub mod day_01;
 pub mod day_02;
@@ -17,3 +24,46 @@ pub mod day_13;
 pub mod day_14;
 pub mod day_15;
 pub mod day_16;
+
+use solution::Day;
+
+/// Every solved day, in calendar order
+pub const DAYS: [Day; 16] = [
+    Day::new::<day_01::Puzzle>(),
+    Day::new::<day_02::Puzzle>(),
+    Day::new::<day_03::Puzzle>(),
+    Day::new::<day_04::Puzzle>(),
+    Day::new::<day_05::Puzzle>(),
+    Day::new::<day_06::Puzzle>(),
+    Day::new::<day_07::Puzzle>(),
+    Day::new::<day_08::Puzzle>(),
+    Day::new::<day_09::Puzzle>(),
+    Day::new::<day_10::Puzzle>(),
+    Day::new::<day_11::Puzzle>(),
+    Day::new::<day_12::Puzzle>(),
+    Day::new::<day_13::Puzzle>(),
+    Day::new::<day_14::Puzzle>(),
+    Day::new::<day_15::Puzzle>(),
+    Day::new::<day_16::Puzzle>(),
+];
+
+/// Looks up a day in the registry by its number
+pub fn day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_test() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.number as usize, i + 1);
+        }
+
+        assert_eq!(day(15).map(|d| d.title), Some("chiton"));
+        assert!(day(0).is_none());
+        assert!(day(26).is_none());
+    }
+}
@@ -0,0 +1,862 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+    ops::Add,
+};
+
+use crate::grid::{Coordinate, Grid};
+
+/// Where a search keeps the cost of reaching each node and the node it was reached from. A
+/// `HashMap` works for any graph, but a store that can index nodes directly saves hashing every
+/// node on the hot path
+pub trait Store<N, C> {
+    fn get(&self, node: N) -> Option<(C, Option<N>)>;
+
+    fn insert(&mut self, node: N, cost: C, parent: Option<N>);
+}
+
+impl<N, C> Store<N, C> for HashMap<N, (C, Option<N>)>
+where
+    N: Copy + Eq + Hash,
+    C: Copy,
+{
+    fn get(&self, node: N) -> Option<(C, Option<N>)> {
+        HashMap::get(self, &node).copied()
+    }
+
+    fn insert(&mut self, node: N, cost: C, parent: Option<N>) {
+        HashMap::insert(self, node, (cost, parent));
+    }
+}
+
+/// A store for searches over a grid, with a cell for every coordinate. Every node reached must be
+/// within the bounds of the grid
+impl<C> Store<Coordinate, C> for Grid<Option<(C, Option<Coordinate>)>>
+where
+    C: Copy,
+{
+    fn get(&self, node: Coordinate) -> Option<(C, Option<Coordinate>)> {
+        self[node]
+    }
+
+    fn insert(&mut self, node: Coordinate, cost: C, parent: Option<Coordinate>) {
+        self[node] = Some((cost, parent));
+    }
+}
+
+/// The nodes reached by a search, along with the cost of reaching each of them and the node each
+/// was reached from, so that paths can be reconstructed
+#[derive(Debug, Clone)]
+pub struct Search<N, C, S = HashMap<N, (C, Option<N>)>> {
+    /// The cost of reaching each node, and the node it was reached from
+    nodes: S,
+    marker: PhantomData<(N, C)>,
+}
+
+impl<N, C, S> Search<N, C, S>
+where
+    N: Copy,
+    C: Copy,
+    S: Store<N, C>,
+{
+    fn new(mut nodes: S, start: N, cost: C) -> Self {
+        nodes.insert(start, cost, None);
+        Self {
+            nodes,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn cost(&self, node: N) -> Option<C> {
+        self.nodes.get(node).map(|(cost, _)| cost)
+    }
+
+    pub fn contains(&self, node: N) -> bool {
+        self.nodes.get(node).is_some()
+    }
+
+    /// Reconstructs the path from the start to the given node, including both ends
+    pub fn path_to(&self, node: N) -> Option<Vec<N>> {
+        self.nodes.get(node)?;
+
+        let mut path = vec![node];
+        let mut cur = node;
+        while let Some((_, Some(parent))) = self.nodes.get(cur) {
+            path.push(parent);
+            cur = parent;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+impl<N, C> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy,
+{
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns an iterator over every reached node, in no particular order
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.keys()
+    }
+}
+
+/// A path found by a shortest path search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+/// Visits every node reachable from the start in breadth first order. The cost of each node is
+/// the number of steps it takes to reach it
+pub fn bfs<N, I>(start: N, mut neighbours: impl FnMut(N) -> I) -> Search<N, usize>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(HashMap::new(), start, 0);
+    let mut q: VecDeque<N> = VecDeque::new();
+    q.push_back(start);
+
+    while let Some(node) = q.pop_front() {
+        let cost = search.nodes[&node].0 + 1;
+        for neighbour in neighbours(node) {
+            if let Entry::Vacant(entry) = search.nodes.entry(neighbour) {
+                entry.insert((cost, Some(node)));
+                q.push_back(neighbour);
+            }
+        }
+    }
+
+    search
+}
+
+/// Visits every node reachable from the start in depth first order. The cost of each node is its
+/// depth in the search tree, which is not necessarily the length of the shortest path to it
+pub fn dfs<N, I>(start: N, mut neighbours: impl FnMut(N) -> I) -> Search<N, usize>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(HashMap::new(), start, 0);
+    let mut stack: Vec<N> = vec![start];
+
+    while let Some(node) = stack.pop() {
+        let cost = search.nodes[&node].0 + 1;
+        for neighbour in neighbours(node) {
+            if let Entry::Vacant(entry) = search.nodes.entry(neighbour) {
+                entry.insert((cost, Some(node)));
+                stack.push(neighbour);
+            }
+        }
+    }
+
+    search
+}
+
+/// Counts the distinct paths from the start state to a goal state, exploring depth first. States
+/// are never deduplicated, so they must carry whatever is needed to stop the walk from looping
+/// (such as the set of nodes already visited). A path ends as soon as it reaches a goal
+pub fn count_paths<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> usize
+where
+    I: IntoIterator<Item = S>,
+{
+    let mut count = 0;
+    let mut stack: Vec<S> = vec![start];
+
+    while let Some(state) = stack.pop() {
+        if is_goal(&state) {
+            count += 1;
+            continue;
+        }
+
+        stack.extend(successors(&state));
+    }
+
+    count
+}
+
+/// Counts the same paths as `count_paths`, but remembers how many paths lead on from each state
+/// so that a state reached along many paths is only explored once. The successors must never
+/// lead back to an earlier state, or the count is undefined. Returns None if there are more paths
+/// than fit in a usize
+pub fn count_paths_memoised<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut counts: HashMap<S, usize> = HashMap::new();
+    // a state is pushed once to queue up its successors, and again underneath them so that it is
+    // summed up after all of them have been counted
+    let mut stack: Vec<(S, bool)> = vec![(start.clone(), false)];
+
+    while let Some((state, expanded)) = stack.pop() {
+        if counts.contains_key(&state) {
+            continue;
+        }
+
+        if is_goal(&state) {
+            counts.insert(state, 1);
+        } else if expanded {
+            let count = successors(&state).into_iter().try_fold(0_usize, |count, next| {
+                count.checked_add(counts.get(&next).copied().unwrap_or(0))
+            })?;
+            counts.insert(state, count);
+        } else {
+            let next: Vec<S> = successors(&state).into_iter().collect();
+            stack.push((state, true));
+            stack.extend(
+                next.into_iter()
+                    .filter(|next| !counts.contains_key(next))
+                    .map(|next| (next, false)),
+            );
+        }
+    }
+
+    counts.get(&start).copied()
+}
+
+/// Finds the most expensive path from the start to a goal, remembering the most expensive way on
+/// from each node like `count_paths_memoised`. A path ends as soon as it reaches a goal, and the
+/// neighbours must never lead back to an earlier node
+pub fn longest_path<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(N) -> I,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // the cost of the most expensive way on from each node and the next node along it, or None
+    // if no goal can be reached from the node
+    let mut best: HashMap<N, Option<(C, Option<N>)>> = HashMap::new();
+    let mut stack: Vec<(N, bool)> = vec![(start, false)];
+
+    while let Some((node, expanded)) = stack.pop() {
+        if best.contains_key(&node) {
+            continue;
+        }
+
+        if is_goal(node) {
+            best.insert(node, Some((C::default(), None)));
+        } else if expanded {
+            let way = neighbours(node)
+                .into_iter()
+                .filter_map(|(next, step)| {
+                    let (cost, _) = best.get(&next).copied().flatten()?;
+                    Some((cost + step, Some(next)))
+                })
+                .max_by_key(|(cost, _)| *cost);
+            best.insert(node, way);
+        } else {
+            let next: Vec<N> = neighbours(node)
+                .into_iter()
+                .map(|(next, _)| next)
+                .filter(|next| !best.contains_key(next))
+                .collect();
+            stack.push((node, true));
+            stack.extend(next.into_iter().map(|next| (next, false)));
+        }
+    }
+
+    let (cost, _) = best[&start]?;
+    let mut nodes = vec![start];
+    let mut cur = start;
+    while let Some(Some((_, Some(next)))) = best.get(&cur) {
+        nodes.push(*next);
+        cur = *next;
+    }
+
+    Some(Path { cost, nodes })
+}
+
+/// Finds the cheapest path from the start to a goal. Edge costs must not be negative
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(N) -> I,
+    is_goal: impl FnMut(N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    dijkstra_in(HashMap::new(), start, neighbours, is_goal)
+}
+
+/// Like `dijkstra`, but keeps the cost of each node in the given (empty) store
+pub fn dijkstra_in<N, C, I, S>(
+    store: S,
+    start: N,
+    neighbours: impl FnMut(N) -> I,
+    is_goal: impl FnMut(N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Copy,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: Store<N, C>,
+{
+    astar_in(store, start, neighbours, |_| C::default(), is_goal)
+}
+
+/// Finds the cheapest path from the start to a goal, guided by a heuristic estimate of the
+/// remaining cost. The heuristic must never overestimate, or the path found may not be cheapest
+pub fn astar<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(N) -> I,
+    heuristic: impl FnMut(N) -> C,
+    is_goal: impl FnMut(N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar_in(HashMap::new(), start, neighbours, heuristic, is_goal)
+}
+
+/// Like `astar`, but keeps the cost of each node in the given (empty) store
+pub fn astar_in<N, C, I, S>(
+    store: S,
+    start: N,
+    mut neighbours: impl FnMut(N) -> I,
+    mut heuristic: impl FnMut(N) -> C,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Copy,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    S: Store<N, C>,
+{
+    let mut search = Search::new(store, start, C::default());
+    let mut q: BinaryHeap<State<N, C>> = BinaryHeap::new();
+    q.push(State {
+        priority: heuristic(start),
+        cost: C::default(),
+        node: start,
+    });
+
+    while let Some(State { cost, node, .. }) = q.pop() {
+        // the first time a goal is popped, it must be by the cheapest path
+        if is_goal(node) {
+            return search.path_to(node).map(|nodes| Path { cost, nodes });
+        }
+
+        // if the node has since been reached more cheaply, then prune this path
+        if search.cost(node).is_some_and(|best| cost > best) {
+            continue;
+        }
+
+        for (neighbour, step) in neighbours(node) {
+            let next_cost = cost + step;
+            if relax(&mut search, node, neighbour, next_cost) {
+                q.push(State {
+                    priority: next_cost + heuristic(neighbour),
+                    cost: next_cost,
+                    node: neighbour,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds the cheapest path from the start to the goal by searching forwards from the start and
+/// backwards from the goal at the same time, stopping once the two searches can no longer find a
+/// cheaper meeting point. `predecessors` must return the nodes with an edge into the given node,
+/// along with the cost of that edge
+pub fn bidirectional_dijkstra<N, C, I, J>(
+    start: N,
+    goal: N,
+    neighbours: impl FnMut(N) -> I,
+    predecessors: impl FnMut(N) -> J,
+) -> Option<Path<N, C>>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    J: IntoIterator<Item = (N, C)>,
+{
+    bidirectional_dijkstra_in(HashMap::new(), start, goal, neighbours, predecessors)
+}
+
+/// Like `bidirectional_dijkstra`, but keeps the cost of each node in copies of the given (empty)
+/// store, one for each direction
+pub fn bidirectional_dijkstra_in<N, C, I, J, S>(
+    store: S,
+    start: N,
+    goal: N,
+    mut neighbours: impl FnMut(N) -> I,
+    mut predecessors: impl FnMut(N) -> J,
+) -> Option<Path<N, C>>
+where
+    N: Copy + Eq,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+    J: IntoIterator<Item = (N, C)>,
+    S: Store<N, C> + Clone,
+{
+    if start == goal {
+        return Some(Path {
+            cost: C::default(),
+            nodes: vec![start],
+        });
+    }
+
+    let mut forward = Search::new(store.clone(), start, C::default());
+    let mut backward = Search::new(store, goal, C::default());
+    let mut forward_q: BinaryHeap<State<N, C>> = BinaryHeap::new();
+    let mut backward_q: BinaryHeap<State<N, C>> = BinaryHeap::new();
+    forward_q.push(State::new(C::default(), start));
+    backward_q.push(State::new(C::default(), goal));
+
+    // the cheapest complete path found so far, and the node where its two halves meet
+    let mut best: Option<(C, N)> = None;
+
+    while let (Some(f), Some(b)) = (forward_q.peek(), backward_q.peek()) {
+        // every path not yet found must cost at least as much as the two frontiers combined
+        if let Some((cost, _)) = best {
+            if f.cost + b.cost >= cost {
+                break;
+            }
+        }
+
+        // expand whichever frontier is cheaper, so that the searches grow evenly
+        let (search, other, q, expand_forward) = if f.cost <= b.cost {
+            (&mut forward, &backward, &mut forward_q, true)
+        } else {
+            (&mut backward, &forward, &mut backward_q, false)
+        };
+
+        let State { cost, node, .. } = q.pop()?;
+        if search.cost(node).is_some_and(|best| cost > best) {
+            continue;
+        }
+
+        let edges: Vec<(N, C)> = if expand_forward {
+            neighbours(node).into_iter().collect()
+        } else {
+            predecessors(node).into_iter().collect()
+        };
+
+        for (next, step) in edges {
+            let next_cost = cost + step;
+            if relax(search, node, next, next_cost) {
+                q.push(State::new(next_cost, next));
+            }
+
+            if let (Some(this_cost), Some(other_cost)) = (search.cost(next), other.cost(next)) {
+                let total = this_cost + other_cost;
+                if best.map(|(cost, _)| total < cost).unwrap_or(true) {
+                    best = Some((total, next));
+                }
+            }
+        }
+    }
+
+    let (cost, meeting) = best?;
+    let mut nodes = forward.path_to(meeting)?;
+    let mut rest = backward.path_to(meeting)?;
+    rest.pop();
+    nodes.extend(rest.into_iter().rev());
+
+    Some(Path { cost, nodes })
+}
+
+/// Finds the cheapest path from the start to a goal using Dial's algorithm, which replaces the
+/// priority queue of Dijkstra with a ring of buckets, one per cost. This is faster when edge costs
+/// are small integers, and `max_step` must be at least the cost of the most expensive edge
+pub fn dial<N, I>(
+    start: N,
+    neighbours: impl FnMut(N) -> I,
+    is_goal: impl FnMut(N) -> bool,
+    max_step: usize,
+) -> Option<Path<N, usize>>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    dial_in(HashMap::new(), start, neighbours, is_goal, max_step)
+}
+
+/// Like `dial`, but keeps the cost of each node in the given (empty) store
+pub fn dial_in<N, I, S>(
+    store: S,
+    start: N,
+    mut neighbours: impl FnMut(N) -> I,
+    mut is_goal: impl FnMut(N) -> bool,
+    max_step: usize,
+) -> Option<Path<N, usize>>
+where
+    N: Copy,
+    I: IntoIterator<Item = (N, usize)>,
+    S: Store<N, usize>,
+{
+    let mut search = Search::new(store, start, 0);
+    let len = max_step + 1;
+    let mut buckets: Vec<Vec<N>> = vec![Vec::new(); len];
+    let mut queued = 1;
+    buckets[0].push(start);
+
+    let mut cost = 0;
+    while queued > 0 {
+        let bucket = cost % len;
+        while let Some(node) = buckets[bucket].pop() {
+            queued -= 1;
+
+            // skip nodes that have since been reached more cheaply
+            if search.cost(node) != Some(cost) {
+                continue;
+            }
+
+            if is_goal(node) {
+                return search.path_to(node).map(|nodes| Path { cost, nodes });
+            }
+
+            for (neighbour, step) in neighbours(node) {
+                assert!(step <= max_step, "edge cost {} exceeds max step", step);
+                let next_cost = cost + step;
+                if relax(&mut search, node, neighbour, next_cost) {
+                    buckets[next_cost % len].push(neighbour);
+                    queued += 1;
+                }
+            }
+        }
+
+        cost += 1;
+    }
+
+    None
+}
+
+/// Records a cheaper way of reaching a node, returning whether the recorded cost was improved
+fn relax<N, C, S>(search: &mut Search<N, C, S>, from: N, to: N, cost: C) -> bool
+where
+    N: Copy,
+    C: Copy + Ord,
+    S: Store<N, C>,
+{
+    if search.cost(to).is_some_and(|best| cost >= best) {
+        return false;
+    }
+
+    search.nodes.insert(to, cost, Some(from));
+    true
+}
+
+/// An entry in the priority queue, ordered so that the lowest priority is popped first
+struct State<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Copy> State<N, C> {
+    /// Creates an entry prioritised by its cost alone
+    fn new(cost: C, node: N) -> Self {
+        Self {
+            priority: cost,
+            cost,
+            node,
+        }
+    }
+}
+
+impl<N, C: Ord> Ord for State<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for State<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for State<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for State<N, C> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::grid::{self, Coordinate, Grid};
+
+    use super::*;
+
+    fn example_grid() -> Grid<usize> {
+        let lines = vec![
+            "1163751742".to_string(),
+            "1381373672".to_string(),
+            "2136511328".to_string(),
+            "3694931569".to_string(),
+            "7463417111".to_string(),
+            "1319128137".to_string(),
+            "1359912421".to_string(),
+            "3125421639".to_string(),
+            "1293138521".to_string(),
+            "2311944581".to_string(),
+        ];
+        Grid::parse(&lines, grid::digit).expect("could not parse input")
+    }
+
+    fn example_graph() -> HashMap<char, Vec<(char, usize)>> {
+        let mut adj: HashMap<char, Vec<(char, usize)>> = HashMap::new();
+        for (a, b, cost) in [('a', 'b', 7), ('a', 'c', 9), ('a', 'f', 14), ('b', 'c', 10)]
+            .iter()
+            .chain(&[('b', 'd', 15), ('c', 'd', 11), ('c', 'f', 2), ('d', 'e', 6)])
+            .chain(&[('e', 'f', 9), ('g', 'h', 1)])
+        {
+            adj.entry(*a).or_default().push((*b, *cost));
+            adj.entry(*b).or_default().push((*a, *cost));
+        }
+        adj
+    }
+
+    #[test]
+    fn bfs_test() {
+        let grid = example_grid();
+        let start = Coordinate::from((0_usize, 0_usize));
+        let end = Coordinate::from((9_usize, 9_usize));
+
+        let search = bfs(start, |c| grid.cardinal_neighbours(c));
+        assert_eq!(search.len(), 100);
+        assert_eq!(search.cost(end), Some(18));
+        assert_eq!(search.path_to(end).map(|path| path.len()), Some(19));
+
+        let graph = example_graph();
+        let search = bfs('a', |n| graph[&n].iter().map(|(m, _)| *m));
+        assert_eq!(search.cost('e'), Some(2));
+        assert_eq!(search.path_to('a'), Some(vec!['a']));
+        assert_eq!(search.path_to('g'), None);
+    }
+
+    #[test]
+    fn dfs_test() {
+        let graph = example_graph();
+
+        let search = dfs('a', |n| graph[&n].iter().map(|(m, _)| *m));
+        let nodes: HashSet<char> = search.nodes().copied().collect();
+        assert_eq!(nodes, "abcdef".chars().collect());
+
+        let path = search.path_to('e').expect("expected a path");
+        assert_eq!(path.first(), Some(&'a'));
+        assert_eq!(path.last(), Some(&'e'));
+    }
+
+    #[test]
+    fn count_paths_test() {
+        // monotone lattice paths through a 3x3 grid of cells
+        let count = count_paths(
+            (0, 0),
+            |&(i, j)| {
+                let mut next = Vec::new();
+                if i < 2 {
+                    next.push((i + 1, j));
+                }
+                if j < 2 {
+                    next.push((i, j + 1));
+                }
+                next
+            },
+            |&state| state == (2, 2),
+        );
+
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn count_paths_memoised_test() {
+        let lattice = |size: usize| {
+            move |&(i, j): &(usize, usize)| {
+                let mut next = Vec::new();
+                if i < size {
+                    next.push((i + 1, j));
+                }
+                if j < size {
+                    next.push((i, j + 1));
+                }
+                next
+            }
+        };
+
+        assert_eq!(count_paths_memoised((0, 0), lattice(2), |&state| state == (2, 2)), Some(6));
+        // far too many paths to walk one at a time
+        assert_eq!(
+            count_paths_memoised((0, 0), lattice(30), |&state| state == (30, 30)),
+            Some(118_264_581_564_861_424)
+        );
+        // more paths than fit in 64 bits
+        assert_eq!(count_paths_memoised((0, 0), lattice(34), |&state| state == (34, 34)), None);
+    }
+
+    #[test]
+    fn longest_path_test() {
+        // a diamond where the long way round is cheaper to start but dearer overall
+        let neighbours = |node: char| match node {
+            'a' => vec![('b', 1), ('c', 5)],
+            'b' => vec![('d', 10)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        };
+
+        let path = longest_path('a', neighbours, |node| node == 'd').unwrap();
+        assert_eq!(path.cost, 11);
+        assert_eq!(path.nodes, vec!['a', 'b', 'd']);
+
+        assert_eq!(longest_path('a', neighbours, |node| node == 'e'), None::<Path<char, usize>>);
+    }
+
+    #[test]
+    fn dijkstra_test() {
+        let grid = example_grid();
+        let start = Coordinate::from((0_usize, 0_usize));
+        let end = Coordinate::from((9_usize, 9_usize));
+
+        let path = dijkstra(
+            start,
+            |c| grid.cardinal_neighbours(c).map(|n| (n, grid[n])),
+            |c| c == end,
+        )
+        .expect("expected a path");
+        assert_eq!(path.cost, 40);
+        assert_eq!(path.nodes.first(), Some(&start));
+        assert_eq!(path.nodes.last(), Some(&end));
+        assert_eq!(path.nodes.iter().skip(1).map(|c| grid[*c]).sum::<usize>(), 40);
+
+        let graph = example_graph();
+        let path = dijkstra('a', |n| graph[&n].iter().copied(), |n| n == 'e').expect("expected a path");
+        assert_eq!(path.cost, 20);
+        assert_eq!(path.nodes, vec!['a', 'c', 'f', 'e']);
+        assert_eq!(dijkstra('a', |n| graph[&n].iter().copied(), |n| n == 'g'), None);
+    }
+
+    #[test]
+    fn grid_store_test() {
+        let grid = example_grid();
+        let start = Coordinate::from((0_usize, 0_usize));
+        let end = Coordinate::from((9_usize, 9_usize));
+        let neighbours = |c| grid.cardinal_neighbours(c).map(|n| (n, grid[n]));
+        let predecessors = |c| {
+            let risk = grid[c];
+            grid.cardinal_neighbours(c).map(move |p| (p, risk))
+        };
+        let store = || Grid::new(grid.n, grid.m, None);
+
+        let expected = dijkstra(start, neighbours, |c| c == end).map(|path| path.cost);
+        let paths = [
+            dijkstra_in(store(), start, neighbours, |c| c == end),
+            astar_in(store(), start, neighbours, |_| 0, |c| c == end),
+            bidirectional_dijkstra_in(store(), start, end, neighbours, predecessors),
+            dial_in(store(), start, neighbours, |c| c == end, 9),
+        ];
+        for path in paths {
+            let path = path.expect("expected a path");
+            assert_eq!(Some(path.cost), expected);
+            assert_eq!(path.nodes.iter().skip(1).map(|c| grid[*c]).sum::<usize>(), path.cost);
+        }
+    }
+
+    #[test]
+    fn bidirectional_dijkstra_test() {
+        let grid = example_grid();
+        let start = Coordinate::from((0_usize, 0_usize));
+        let end = Coordinate::from((9_usize, 9_usize));
+
+        // entering a cell costs its risk, so leaving it backwards does too
+        let path = bidirectional_dijkstra(
+            start,
+            end,
+            |c| grid.cardinal_neighbours(c).map(|n| (n, grid[n])),
+            |c| {
+                let risk = grid[c];
+                grid.cardinal_neighbours(c).map(move |p| (p, risk))
+            },
+        )
+        .expect("expected a path");
+        assert_eq!(path.cost, 40);
+        assert_eq!(path.nodes.first(), Some(&start));
+        assert_eq!(path.nodes.last(), Some(&end));
+        assert_eq!(path.nodes.iter().skip(1).map(|c| grid[*c]).sum::<usize>(), 40);
+
+        let graph = example_graph();
+        let edges = |n: char| graph[&n].iter().copied();
+        let path = bidirectional_dijkstra('a', 'e', edges, edges).expect("expected a path");
+        assert_eq!(path.cost, 20);
+        assert_eq!(path.nodes, vec!['a', 'c', 'f', 'e']);
+        assert_eq!(bidirectional_dijkstra('a', 'g', edges, edges), None);
+        assert_eq!(
+            bidirectional_dijkstra('a', 'a', edges, edges).map(|path| path.nodes),
+            Some(vec!['a'])
+        );
+    }
+
+    #[test]
+    fn dial_test() {
+        let grid = example_grid();
+        let start = Coordinate::from((0_usize, 0_usize));
+        let end = Coordinate::from((9_usize, 9_usize));
+
+        let path = dial(
+            start,
+            |c| grid.cardinal_neighbours(c).map(|n| (n, grid[n])),
+            |c| c == end,
+            9,
+        )
+        .expect("expected a path");
+        assert_eq!(path.cost, 40);
+        assert_eq!(path.nodes.iter().skip(1).map(|c| grid[*c]).sum::<usize>(), 40);
+
+        let graph = example_graph();
+        let path = dial('a', |n| graph[&n].iter().copied(), |n| n == 'e', 15).expect("expected a path");
+        assert_eq!(path.nodes, vec!['a', 'c', 'f', 'e']);
+        assert_eq!(dial('a', |n| graph[&n].iter().copied(), |n| n == 'g', 15), None);
+    }
+
+    #[test]
+    fn astar_test() {
+        let grid = example_grid();
+        let start = Coordinate::from((0_usize, 0_usize));
+        let end = Coordinate::from((9_usize, 9_usize));
+
+        let path = astar(
+            start,
+            |c| grid.cardinal_neighbours(c).map(|n| (n, grid[n])),
+            |c| ((end.row() - c.row()) + (end.col() - c.col())) as usize,
+            |c| c == end,
+        )
+        .expect("expected a path");
+        assert_eq!(path.cost, 40);
+    }
+}
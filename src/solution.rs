@@ -0,0 +1,173 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::{AocError, Result},
+    utils,
+};
+
+/// A solution for a single day of the advent calendar. Parsing is kept separate from the two
+/// parts so that the parsed input can be shared between them
+pub trait Solution {
+    /// The day of the advent calendar this solution is for
+    const DAY: u8;
+
+    /// The title of the puzzle for the day
+    const TITLE: &'static str;
+
+    type Input;
+    type PartOne: Display;
+    type PartTwo: Display;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input>;
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne>;
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// The rendered answer to a single part, along with how long it took to solve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub part: Part,
+    pub value: String,
+    pub elapsed: Duration,
+}
+
+/// The outcome of parsing the input and solving the requested parts of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub parse: Duration,
+    pub answers: Vec<Answer>,
+}
+
+impl Report {
+    pub fn answer(&self, part: Part) -> Option<&Answer> {
+        self.answers.iter().find(|answer| answer.part == part)
+    }
+
+    /// Returns the total time spent parsing and solving
+    pub fn elapsed(&self) -> Duration {
+        self.parse + self.answers.iter().map(|answer| answer.elapsed).sum::<Duration>()
+    }
+}
+
+/// The rendered answers to both parts of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part_one: String,
+    pub part_two: String,
+}
+
+/// Parses the given lines and solves the requested parts of the puzzle, timing each step
+pub fn execute<S: Solution>(lines: Vec<String>, parts: &[Part]) -> Result<Report> {
+    let start = Instant::now();
+    let input = S::parse_input(lines)?;
+    let parse = start.elapsed();
+
+    let mut answers = Vec::with_capacity(parts.len());
+    for part in parts {
+        let start = Instant::now();
+        let value = match part {
+            Part::One => S::part_one(&input)?.to_string(),
+            Part::Two => S::part_two(&input)?.to_string(),
+        };
+
+        answers.push(Answer {
+            part: *part,
+            value,
+            elapsed: start.elapsed(),
+        });
+    }
+
+    Ok(Report { parse, answers })
+}
+
+/// Returns the default input path for the given day
+pub fn input_path(day: u8) -> String {
+    format!("inputs/day_{:02}", day)
+}
+
+/// Returns the path of the example input for the given day
+pub fn example_path(day: u8) -> String {
+    format!("{}.example", input_path(day))
+}
+
+/// A type erased entry in the day registry, so that days can be enumerated and run by number
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    pub title: &'static str,
+    execute: fn(Vec<String>, &[Part]) -> Result<Report>,
+}
+
+impl Day {
+    pub const fn new<S: Solution>() -> Self {
+        Self {
+            number: S::DAY,
+            title: S::TITLE,
+            execute: execute::<S>,
+        }
+    }
+
+    pub fn execute(&self, lines: Vec<String>, parts: &[Part]) -> Result<Report> {
+        (self.execute)(lines, parts)
+    }
+
+    pub fn solve(&self, lines: Vec<String>) -> Result<Answers> {
+        let report = self.execute(lines, &Part::BOTH)?;
+        let mut answers = report.answers.into_iter().map(|answer| answer.value);
+
+        match (answers.next(), answers.next()) {
+            (Some(part_one), Some(part_two)) => Ok(Answers { part_one, part_two }),
+            _ => Err(AocError::no_solution("expected answers for both parts")),
+        }
+    }
+
+    /// Loads the input for this day, respecting `AOC_INPUT`
+    pub fn load_input(&self) -> Result<Vec<String>> {
+        utils::load_input(&input_path(self.number))
+    }
+
+    /// Loads the input for this day, respecting `AOC_INPUT`, then solves both parts
+    pub fn run(&self) -> Result<Answers> {
+        self.solve(self.load_input()?)
+    }
+
+    /// Runs this day and prints the answers to both parts
+    pub fn print(&self) -> Result<()> {
+        let answers = self.run()?;
+
+        print_answer(Part::One, &answers.part_one);
+        print_answer(Part::Two, &answers.part_two);
+        Ok(())
+    }
+}
+
+/// Prints an answer, putting multi-line answers (such as rendered dots) below the label
+pub fn print_answer(part: Part, answer: &str) {
+    if answer.contains('\n') {
+        println!("part {}:\n{}", part.number(), answer);
+    } else {
+        println!("part {}: {}", part.number(), answer);
+    }
+}
@@ -4,13 +4,26 @@ use std::{
     io::{self, BufRead, BufReader},
 };
 
-pub fn load_input(default_path: &str) -> Result<Vec<String>, io::Error> {
+use crate::error::Result;
+
+pub fn load_input(default_path: &str) -> Result<Vec<String>> {
     let path = env::var("AOC_INPUT").unwrap_or(default_path.to_string());
     load_lines(&path)
 }
 
-pub fn load_lines(path: &str) -> Result<Vec<String>, io::Error> {
-    BufReader::new(File::open(path)?).lines().collect()
+pub fn load_lines(path: &str) -> Result<Vec<String>> {
+    let lines = BufReader::new(File::open(path)?)
+        .lines()
+        .collect::<io::Result<Vec<String>>>()?;
+    Ok(lines)
+}
+
+pub fn load_stdin() -> Result<Vec<String>> {
+    let lines = io::stdin()
+        .lock()
+        .lines()
+        .collect::<io::Result<Vec<String>>>()?;
+    Ok(lines)
 }
 
 pub fn split_lines(s: &str) -> Vec<String> {
@@ -1,9 +1,8 @@
-use aoc_2021::{day_xx::{parse_input, self}, utils};
+use aoc_2021::{day_xx, solution::Day};
 
 fn main() {
-    let lines = utils::load_input("inputs/day_xx").expect("could not load input");
-    let _ = parse_input(lines).expect("could not parse input");
-
-    println!("part 1: {}", day_xx::part_one(&_));
-    println!("part 2: {}", day_xx::part_two(&_));
+    if let Err(e) = Day::new::<day_xx::Puzzle>().print() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
@@ -1,12 +1,37 @@
-pub fn parse_input(lines: Vec<String>) -> Result<_, String> {
+use crate::{error::AocError, solution::Solution};
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    const DAY: u8 = xx;
+    const TITLE: &'static str = "REPLACE ME";
+
+    type Input = Vec<_>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse_input(lines: Vec<String>) -> Result<Self::Input, AocError> {
+        parse_input(lines)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne, AocError> {
+        part_one(input)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo, AocError> {
+        part_two(input)
+    }
+}
+
+pub fn parse_input(lines: Vec<String>) -> Result<_, AocError> {
     todo!()
 }
 
-pub fn part_one(_: Vec<_>) -> i64 {
+pub fn part_one(_: &[_]) -> Result<i64, AocError> {
     todo!()
 }
 
-pub fn part_two(_: Vec<_>) -> i64 {
+pub fn part_two(_: &[_]) -> Result<i64, AocError> {
     todo!()
 }
 